@@ -5,7 +5,7 @@ use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
-use core::fmt::Write;
+use core::fmt::{Display, Formatter, Write};
 
 /// Newline symbol in common platforms.
 ///
@@ -28,110 +28,268 @@ pub struct Dumper<'a, R: Repr> {
     root: Root,
     level: usize,
     anchors: &'a Anchors<R>,
+    flow: bool,
+    indent: usize,
+    canonical_numbers: bool,
 }
 
 impl<'a, R: Repr> Dumper<'a, R> {
     /// Create the dumper.
     pub fn new(node: &'a Node<R>, anchors: &'a Anchors<R>) -> Self {
-        Self { node, root: Root::Scalar, level: 0, anchors }
+        Self {
+            node,
+            root: Root::Scalar,
+            level: 0,
+            anchors,
+            flow: false,
+            indent: 2,
+            canonical_numbers: false,
+        }
+    }
+
+    /// Dump the collections in flow style (`[]` and `{}`), which is a single
+    /// line output.
+    ///
+    /// ```
+    /// use yaml_peg::{dumper::Dumper, node, parser::Anchors};
+    ///
+    /// let n = node!({"a" => 1, "b" => node!([2, 3]), "c d" => "e: f"});
+    /// let doc = Dumper::new(&n, &Anchors::new()).flow(true).dump();
+    /// assert_eq!(doc, r#"{a: 1, b: [2, 3], c d: "e: f"}"#);
+    /// ```
+    pub fn flow(self, flow: bool) -> Self {
+        Self { flow, ..self }
+    }
+
+    /// The indentation width of the nested block collections, default to 2
+    /// spaces. The width smaller than 2 is treated as 2, otherwise the block
+    /// sequence cannot be nested.
+    ///
+    /// ```
+    /// use yaml_peg::{dumper::{Dumper, NL}, node, parser::Anchors};
+    ///
+    /// let n = node!({"a" => node!([node!({"b" => 1, "c" => 2})])});
+    /// let doc = Dumper::new(&n, &Anchors::new()).indent(4).dump();
+    /// assert_eq!(doc, "a:\n    -   b: 1\n        c: 2".replace('\n', NL));
+    /// ```
+    pub fn indent(self, indent: usize) -> Self {
+        Self { indent: indent.max(2), ..self }
+    }
+
+    /// Dump the numbers in their canonical decimal form, such as `0x1A` as
+    /// `26` and `1_000.5` as `1000.5`. By default, the numbers are dumped as
+    /// they are written.
+    ///
+    /// ```
+    /// use yaml_peg::{dumper::Dumper, parse, parser::Anchors, repr::RcRepr};
+    ///
+    /// let n = parse::<RcRepr>("[0x1A, 1_000, 2e-4]").unwrap().remove(0);
+    /// let doc = Dumper::new(&n, &Anchors::new()).flow(true).dump();
+    /// assert_eq!(doc, "[0x1A, 1_000, 2e-4]");
+    /// let doc = Dumper::new(&n, &Anchors::new())
+    ///     .flow(true)
+    ///     .canonical_numbers(true)
+    ///     .dump();
+    /// assert_eq!(doc, "[26, 1000, 0.0002]");
+    /// ```
+    pub fn canonical_numbers(self, canonical_numbers: bool) -> Self {
+        Self { canonical_numbers, ..self }
     }
 
-    fn part(&self, node: &'a Node<R>, root: Root, level: usize) -> String {
-        Self { node, root, level, anchors: self.anchors }.dump()
+    fn part(&self, node: &'a Node<R>, root: Root, level: usize, seen: &mut Vec<&'a str>) -> String {
+        Self { node, root, level, ..*self }.dump_inner(seen)
     }
 
     /// Dump into string.
+    ///
+    /// The first node of an anchor is dumped with the anchor `&a`, and the
+    /// nodes share the same data with it are dumped as the alias `*a`.
+    ///
+    /// ```
+    /// use yaml_peg::{dumper::{Dumper, NL}, parser::Loader, repr::RcRepr};
+    ///
+    /// let mut loader = Loader::<RcRepr>::new(b"a: &x [1, 2]\nb: *x");
+    /// let root = loader.parse().unwrap();
+    /// let anchors = loader.get_anchors();
+    /// let doc = Dumper::new(&root[0], &anchors[0]).flow(true).dump();
+    /// assert_eq!(doc, "{a: &x [1, 2], b: *x}");
+    /// ```
     pub fn dump(&self) -> String {
+        self.dump_inner(&mut Vec::new())
+    }
+
+    fn dump_inner(&self, seen: &mut Vec<&'a str>) -> String {
         let mut doc = String::new();
-        if let Some(a) = self
-            .anchors
-            .iter()
-            .find_map(|(k, v)| if v == self.node { Some(k) } else { None })
-        {
+        if let Some(a) = self.anchors.iter().find_map(|(k, v)| {
+            let shared = core::ptr::eq(v.yaml(), self.node.yaml());
+            if shared || v.pos() == self.node.pos() && v == self.node {
+                Some(k.as_str())
+            } else {
+                None
+            }
+        }) {
+            if seen.contains(&a) {
+                return format!("*{a}");
+            }
+            seen.push(a);
             write!(doc, "&{a} ").unwrap();
         }
         let tag = self.node.tag();
-        if !tag.is_empty() && !tag.starts_with(parser::tag_prefix!()) {
-            if tag.starts_with(parser::tag_prefix!()) {
-                write!(doc, "!!{tag} ").unwrap();
-            } else if parser::Parser::new(tag.as_bytes()).identifier().is_ok() {
-                write!(doc, "!{tag} ").unwrap();
-            } else {
-                write!(doc, "!<{tag}> ").unwrap();
+        if let Some(ty) = tag.strip_prefix(parser::tag_prefix!()) {
+            if ty != default_type(self.node.yaml()) {
+                write!(doc, "!!{} ", percent_encode(ty, b"-#;/?:@&=+$_.~*'()")).unwrap();
             }
+        } else if tag
+            .strip_prefix('!')
+            .is_some_and(|t| parser::Parser::new(t.as_bytes()).identifier().is_ok())
+        {
+            write!(doc, "{tag} ").unwrap();
+        } else if !tag.is_empty() {
+            let tag = percent_encode(tag, b"-#;/?:@&=+$,_.!~*'()[]");
+            write!(doc, "!<{tag}> ").unwrap();
         }
-        let ind = "  ".repeat(self.level);
+        let ind = " ".repeat(self.indent * self.level);
         match &self.node.yaml() {
             Yaml::Null => doc += "null",
             Yaml::Bool(b) => write!(doc, "{b}").unwrap(),
-            Yaml::Int(n) | Yaml::Float(n) => doc += n,
+            Yaml::Int(n) if self.canonical_numbers => match to_i64(n) {
+                Ok(n) => write!(doc, "{n}").unwrap(),
+                Err(_) => doc += n,
+            },
+            Yaml::Int(n) | Yaml::Timestamp(n) => doc += n,
+            // Quote the empty string, otherwise it will be a null
+            Yaml::Bytes(b) if b.is_empty() => doc += "''",
+            Yaml::Bytes(b) => doc += &to_base64(b),
+            Yaml::Float(n) => {
+                let canonical = match to_f64(n) {
+                    Ok(f) if self.canonical_numbers => Some(format!("{f:?}")),
+                    _ => None,
+                };
+                match canonical.as_deref().unwrap_or(n) {
+                    "NaN" => doc += ".nan",
+                    "inf" => doc += ".inf",
+                    "-inf" => doc += "-.inf",
+                    n if n.contains(['.', 'e', 'E']) => doc += n,
+                    n => write!(doc, "{n}.0").unwrap(),
+                }
+            }
+            Yaml::Str(s) if self.node.style() == Style::DoubleQuoted => doc += &quote(s),
+            Yaml::Str(s)
+                if self.node.style() == Style::SingleQuoted && !s.contains(char::is_control) =>
+            {
+                write!(doc, "'{}'", s.replace('\'', "''")).unwrap();
+            }
+            Yaml::Str(s) if self.flow => {
+                if is_plain::<R>(s, true) {
+                    doc += s;
+                } else {
+                    doc += &quote(s);
+                }
+            }
+            Yaml::Seq(v) if self.flow => {
+                let v = v
+                    .iter()
+                    .map(|node| self.part(node, Root::Array, self.level + 1, seen))
+                    .collect::<Vec<_>>();
+                write!(doc, "[{}]", v.join(", ")).unwrap();
+            }
+            Yaml::Map(m) if self.flow => {
+                let m = m
+                    .iter()
+                    .map(|(k, v)| {
+                        let k = self.part(k, Root::Map, self.level + 1, seen);
+                        let v = self.part(v, Root::Map, self.level + 1, seen);
+                        format!("{k}: {v}")
+                    })
+                    .collect::<Vec<_>>();
+                write!(doc, "{{{}}}", m.join(", ")).unwrap();
+            }
+            Yaml::Str(s)
+                if (s.contains('\n')
+                    || matches!(self.node.style(), Style::Literal | Style::Folded))
+                    && !s.trim().is_empty() =>
+            {
+                // Multiline string, the folded style is dumped as literal
+                let body = s.trim_end_matches('\n');
+                let chomp = match s.len() - body.len() {
+                    0 => "-",
+                    1 => "",
+                    _ => "+",
+                };
+                let ind_indicator = if body.starts_with(' ') { "2" } else { "" };
+                let ind = " ".repeat(self.indent * self.level.max(1));
+                write!(doc, "|{ind_indicator}{chomp}").unwrap();
+                for line in s.trim_end_matches('\n').split('\n') {
+                    doc += NL;
+                    if !line.is_empty() {
+                        doc += &ind;
+                        doc += line;
+                    }
+                }
+                for _ in 1..s.len() - body.len() {
+                    doc += NL;
+                }
+            }
             Yaml::Str(s) => {
-                if s.lines().nth(1).is_some() {
-                    // Multiline string
-                    let s = s
-                        .lines()
-                        .map(|s| {
-                            if s.is_empty() {
-                                String::new()
-                            } else {
-                                ind.to_string() + s.trim_end()
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                        .join(NL);
-                    write!(doc, "|{NL}{ind}{}", s.trim()).unwrap();
-                } else if parser::Parser::new(s.as_bytes())
-                    .string_plain(0, false)
-                    .is_err()
-                {
-                    // Literal string, not plain string
-                    write!(doc, "{s:?}").unwrap();
+                if !is_plain::<R>(s, false) {
+                    // Quoted string, the plain form will be parsed as other data
+                    doc += &quote(s);
                 } else {
                     // Single line string
                     doc += s;
                 }
             }
+            Yaml::Seq(v) if v.is_empty() => doc += "[]",
+            Yaml::Map(m) if m.is_empty() => doc += "{}",
             Yaml::Seq(v) => {
-                let mut buf = NL.to_string();
-                for (i, node) in v.iter().enumerate() {
-                    if i != 0 || self.level != 0 {
-                        buf += &ind;
+                doc.truncate(doc.trim_end().len());
+                for node in v {
+                    doc += NL;
+                    doc += &ind;
+                    let s = self.part(node, Root::Array, self.level + 1, seen);
+                    if s.starts_with(NL) {
+                        write!(doc, "-{s}").unwrap();
+                    } else if s.contains('\n') && matches!(node.yaml(), Yaml::Seq(_) | Yaml::Map(_))
+                    {
+                        // Align the compact collection to the indentation
+                        write!(doc, "-{}{s}", " ".repeat(self.indent - 1)).unwrap();
+                    } else {
+                        write!(doc, "- {s}").unwrap();
                     }
-                    let s = self.part(node, Root::Array, self.level + 1);
-                    write!(buf, "- {s}{NL}").unwrap();
                 }
-                buf.truncate(buf.len() - NL.len());
-                doc += &buf;
             }
             Yaml::Map(m) => {
-                let mut buf = match self.root {
-                    Root::Map => NL.to_string(),
-                    _ => String::new(),
-                };
+                // The properties of the map must be placed at the previous line
+                let new_line = self.root == Root::Map || !doc.is_empty();
+                doc.truncate(doc.trim_end().len());
                 for (i, (k, v)) in m.iter().enumerate() {
-                    if i != 0 || self.root == Root::Map {
-                        buf += &ind;
+                    if i != 0 || new_line {
+                        doc += NL;
+                        doc += &ind;
                     }
-                    let s = self.part(k, Root::Map, self.level + 1);
-                    if matches!(k.yaml(), Yaml::Map(_) | Yaml::Seq(_)) {
-                        let pre_ind = "  ".repeat(self.level + 1);
-                        write!(buf, "?{pre_ind}{NL}{s}{NL}{ind}").unwrap();
+                    let s = self.part(k, Root::Map, self.level + 1, seen);
+                    if s.contains('\n') {
+                        // Complex key
+                        doc += "?";
+                        if !s.starts_with(NL) {
+                            doc += " ";
+                        }
+                        write!(doc, "{s}{NL}{ind}").unwrap();
                     } else {
-                        buf += &s;
-                    };
-                    buf += ":";
-                    buf += &match v.yaml() {
-                        Yaml::Map(_) => self.part(v, Root::Map, self.level + 1),
-                        Yaml::Seq(_) if self.root == Root::Array && i == 0 => {
-                            self.part(v, Root::Map, self.level)
+                        doc += &s;
+                    }
+                    doc += ":";
+                    let s = match v.yaml() {
+                        Yaml::Seq(s) if !s.is_empty() && self.root == Root::Array && i == 0 => {
+                            self.part(v, Root::Map, self.level, seen)
                         }
-                        Yaml::Seq(_) => self.part(v, Root::Map, self.level + 1),
-                        _ => format!(" {}", self.part(v, Root::Map, self.level + 1)),
+                        _ => self.part(v, Root::Map, self.level + 1, seen),
                     };
-                    buf += NL;
+                    if !s.starts_with(NL) {
+                        doc += " ";
+                    }
+                    doc += &s;
                 }
-                buf.truncate(buf.len() - NL.len());
-                doc += &buf;
             }
             Yaml::Alias(a) => write!(doc, "*{a}").unwrap(),
         };
@@ -139,10 +297,123 @@ impl<'a, R: Repr> Dumper<'a, R> {
     }
 }
 
+/// Print the node as a YAML document without anchors.
+///
+/// ```
+/// use yaml_peg::{dumper::NL, node};
+///
+/// assert_eq!("hello", node!("hello").to_string());
+/// let n = node!({"a" => node!([1, true])});
+/// assert_eq!("a:\n  - 1\n  - true".replace('\n', NL), n.to_string());
+/// ```
+impl<R: Repr> Display for Node<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let doc = Dumper::new(self, &Anchors::new()).dump();
+        f.write_str(doc.trim_start_matches(NL))
+    }
+}
+
+/// Print the data as a YAML document.
+impl<R: Repr> Display for Yaml<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&Node::from(self.clone()), f)
+    }
+}
+
+/// Return the type name of the default tag.
+fn default_type<R: Repr>(yaml: &Yaml<R>) -> &'static str {
+    match yaml {
+        Yaml::Null => "null",
+        Yaml::Bool(_) => "bool",
+        Yaml::Int(_) => "int",
+        Yaml::Float(_) => "float",
+        Yaml::Str(_) => "str",
+        Yaml::Timestamp(_) => "timestamp",
+        // The base64 string is a string without the tag
+        Yaml::Bytes(_) => "",
+        Yaml::Seq(_) => "seq",
+        Yaml::Map(_) => "map",
+        Yaml::Alias(_) => "",
+    }
+}
+
+/// Return true if the string can be parsed back from its plain form.
+///
+/// The string should not start with an indicator, contain the separators
+/// of the map and the comment, or be resolved as other data. The flow form
+/// additionally excludes the flow indicators.
+fn is_plain<R: Repr>(s: &str, flow: bool) -> bool {
+    let mut chars = s.chars();
+    let indicator = match chars.next() {
+        // The indicators which are followed by the separators
+        Some('-' | '?' | ':') => matches!(
+            chars.next(),
+            None | Some(':' | ',' | '[' | ']' | '{' | '}' | ' ')
+        ),
+        Some(c) => "[]{},#&*!|>'\"%@`\u{feff}".contains(c),
+        None => return false,
+    };
+    if indicator
+        || s.trim() != s
+        || s.contains(char::is_control)
+        || s.contains(": ")
+        || s.contains(" #")
+        || s.ends_with(':')
+        || s.starts_with("---")
+        || s.starts_with("...")
+        || flow && s.contains([',', '[', ']', '{', '}'])
+        || parser::keyword::<R>(s, false).is_some()
+    {
+        return false;
+    }
+    // The numbers and the timestamp
+    let p = || parser::Parser::new(s.as_bytes());
+    !(p().timestamp().is_ok()
+        || p().float().is_ok()
+        || p().sci_float().is_ok()
+        || p().int().is_ok())
+}
+
+/// Encode the characters of the tag URI which are not alphanumeric or in the
+/// `keep` set, such as `%20` for a space.
+fn percent_encode(s: &str, keep: &[u8]) -> String {
+    let mut doc = String::new();
+    for c in s.bytes() {
+        if c.is_ascii_alphanumeric() || keep.contains(&c) {
+            doc.push(char::from(c));
+        } else {
+            write!(doc, "%{c:02X}").unwrap();
+        }
+    }
+    doc
+}
+
+/// Quote the string with double quotes and escape sequences.
+fn quote(s: &str) -> String {
+    let mut doc = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => doc += "\\\"",
+            '\\' => doc += "\\\\",
+            '\0' => doc += "\\0",
+            '\t' => doc += "\\t",
+            '\n' => doc += "\\n",
+            '\r' => doc += "\\r",
+            c if c.is_control() => write!(doc, "\\u{:04X}", c as u32).unwrap(),
+            c => doc.push(c),
+        }
+    }
+    doc.push('"');
+    doc
+}
+
 /// Dump the YAML data in to block format.
 ///
 /// Dumper will use plain string when the string is none-wrapped,
 /// otherwise it use literal string and trim the last white spaces.
+/// The strings will be quoted if their plain form is another data type,
+/// and the empty collections are dumped in flow style (`[]` and `{}`).
+/// The quoted and block strings keep their [`Style`] from the parser.
 ///
 /// ```
 /// use yaml_peg::{dump, node, dumper::NL};
@@ -177,7 +448,7 @@ pub fn dump<R: Repr>(nodes: &[Node<R>], anchors: &[Anchors<R>]) -> String {
             };
             let doc = Dumper::new(node, anchors).dump() + NL;
             match i {
-                0 => doc,
+                0 => doc.trim_start_matches(NL).to_string(),
                 _ => format!("---{NL}{}", doc.trim_start()),
             }
         })
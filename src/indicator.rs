@@ -20,18 +20,57 @@ use alloc::{format, string::String};
 ///
 /// This may be what you need if you went to indicate an error on the invalid
 /// data.
-pub fn indicated_msg(doc: &[u8], mut pos: u64) -> String {
-    for (line, str_line) in doc.split(|c| *c == b'\n').enumerate() {
-        let full_line = str_line.len() as u64 + 1;
+///
+/// The tabs before the column are kept in the marker line, so the caret is
+/// aligned with the same tab width as the document line.
+///
+/// ```
+/// use yaml_peg::indicated_msg;
+///
+/// let doc = indicated_msg(b"a:\t[b\t}", 6);
+/// assert_eq!(doc, "1:7\na:\t[b\t}\n  \t  \t^")
+/// ```
+pub fn indicated_msg(doc: &[u8], pos: u64) -> String {
+    let (line, column) = pos_to_line_col(doc, pos);
+    let (str_line, _) = lines(doc).nth(line - 1).unwrap();
+    let str_line = String::from_utf8_lossy(str_line);
+    let mut pad = str_line
+        .chars()
+        .take(column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    let n = pad.chars().count();
+    pad.push_str(&" ".repeat(column - 1 - n));
+    format!("{line}:{column}\n{str_line}\n{pad}^")
+}
+
+/// Convert the position of the documentation into line number and column
+/// number, both start from 1.
+///
+/// The column number is counted by the characters instead of bytes.
+/// The line breaks can be `\n`, `\r\n` or `\r`.
+///
+/// ```
+/// use yaml_peg::pos_to_line_col;
+///
+/// let doc = "a: b\n中文: c".as_bytes();
+/// assert_eq!(pos_to_line_col(doc, 0), (1, 1));
+/// assert_eq!(pos_to_line_col(doc, 5), (2, 1));
+/// assert_eq!(pos_to_line_col(doc, 13), (2, 5));
+/// let doc = b"a: b\r\nc: d\re";
+/// assert_eq!(pos_to_line_col(doc, 6), (2, 1));
+/// assert_eq!(pos_to_line_col(doc, 11), (3, 1));
+/// ```
+pub fn pos_to_line_col(doc: &[u8], mut pos: u64) -> (usize, usize) {
+    for (line, (str_line, nl)) in lines(doc).enumerate() {
+        let full_line = (str_line.len() + nl) as u64;
         if full_line > pos {
-            let column = pos;
-            return format!(
-                "{}:{}\n{}\n{}^",
-                line + 1,
-                column + 1,
-                String::from_utf8_lossy(str_line),
-                " ".repeat(column as usize)
-            );
+            let pos = pos as usize;
+            let column = String::from_utf8_lossy(&str_line[..pos.min(str_line.len())])
+                .chars()
+                .count()
+                + pos.saturating_sub(str_line.len());
+            return (line + 1, column + 1);
         } else {
             pos -= full_line;
         }
@@ -39,6 +78,26 @@ pub fn indicated_msg(doc: &[u8], mut pos: u64) -> String {
     unreachable!()
 }
 
+/// Split the lines with their line break length, the last line has a
+/// virtual line break.
+fn lines(doc: &[u8]) -> impl Iterator<Item = (&[u8], usize)> {
+    let mut doc = Some(doc);
+    core::iter::from_fn(move || {
+        let s = doc?;
+        match s.iter().position(|c| matches!(c, b'\n' | b'\r')) {
+            Some(i) => {
+                let nl = if s[i..].starts_with(b"\r\n") { 2 } else { 1 };
+                doc = Some(&s[i + nl..]);
+                Some((&s[..i], nl))
+            }
+            None => {
+                doc = None;
+                Some((s, 1))
+            }
+        }
+    })
+}
+
 /// Same as [`indicated_msg`], but join the path before message.
 ///
 /// ```
@@ -0,0 +1,67 @@
+use crate::{repr::Repr, to_f64, to_i128, Node, Yaml};
+use alloc::string::ToString;
+use serde_json::{Map, Value};
+
+impl<R: Repr> Node<R> {
+    /// Convert the node into [`serde_json::Value`] directly.
+    ///
+    /// + The maps become objects, the keys are converted into strings, and
+    ///   the collection keys are not allowed.
+    /// + The sequences become arrays, and the binary data become arrays of
+    ///   bytes.
+    /// + The integers and floats become numbers, NaN and infinity become
+    ///   null. The integers out of the [`i64`] and [`u64`] range are not
+    ///   allowed, since they cannot be represented without rounding.
+    /// + The aliases are not allowed, use [`Node::resolve_anchors`] before
+    ///   converting the cyclic mode nodes.
+    ///
+    /// Return `Err` with the position of the node which cannot be converted.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use yaml_peg::{parse, repr::RcRepr};
+    ///
+    /// let n = parse::<RcRepr>("a: [1, 2.5, b, ~]\n3: true").unwrap().remove(0);
+    /// let v = n.to_json_value().unwrap();
+    /// assert_eq!(v, json!({"a": [1, 2.5, "b", null], "3": true}));
+    /// ```
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "json")))]
+    pub fn to_json_value(&self) -> Result<Value, u64> {
+        let v = match self.yaml() {
+            Yaml::Null => Value::Null,
+            Yaml::Bool(b) => Value::Bool(*b),
+            // Rounding is not allowed
+            Yaml::Int(s) => {
+                let n = to_i128(s).map_err(|_| self.pos())?;
+                match (i64::try_from(n), u64::try_from(n)) {
+                    (Ok(n), _) => n.into(),
+                    (_, Ok(n)) => n.into(),
+                    _ => return Err(self.pos()),
+                }
+            }
+            Yaml::Float(s) => to_f64(s).map_err(|_| self.pos())?.into(),
+            Yaml::Str(s) | Yaml::Timestamp(s) => Value::String(s.clone()),
+            Yaml::Bytes(b) => b.iter().map(|&c| Value::from(c)).collect(),
+            Yaml::Seq(v) => v
+                .iter()
+                .map(Self::to_json_value)
+                .collect::<Result<_, _>>()?,
+            Yaml::Map(m) => {
+                let mut map = Map::new();
+                for (k, v) in m {
+                    let key = match k.to_json_value()? {
+                        Value::String(s) => s,
+                        Value::Null => "null".to_string(),
+                        Value::Bool(b) => b.to_string(),
+                        Value::Number(n) => n.to_string(),
+                        _ => return Err(k.pos()),
+                    };
+                    map.insert(key, v.to_json_value()?);
+                }
+                Value::Object(map)
+            }
+            Yaml::Alias(_) => return Err(self.pos()),
+        };
+        Ok(v)
+    }
+}
@@ -31,13 +31,21 @@
 //!
 //! + [`parse`]: The parser will replace the anchors during parsing.
 //! + [`parse_cyclic`]: Cyclic data means that a parent alias is inserted at the
-//! child node.   Keep the alias to avoid having undefined anchors when parsing.
+//!   child node. Keep the alias to avoid having undefined anchors when parsing.
+//!
+//! The merge key `<<` of maps will insert the items from the anchored maps,
+//! but the explicit keys are prior.
 //!
 //! # No Standard Library
 //!
 //! The `std` feature is a default feature, use `--no-default-features` to build
 //! in the no-std mode.
 //!
+//! The parser and the [`Node`] type only require the `alloc` crate, and the
+//! parser returns [`parser::PError`] in both modes. The `std` feature provides
+//! the `parse_reader` function and the conversions from [`parser::PError`]
+//! to `std::error::Error` and `std::io::Error`.
+//!
 //! # Serialization and Deserialization
 //!
 //! Enable `serde` feature to use `serde` crate,
@@ -48,17 +56,22 @@
 //!
 //! On the other hand, the primitive types are still able to transform to YAML
 //! data without serialization, according to built-in `From` and `Into` traits.
+//!
+//! Enable `json` feature to convert the nodes into `serde_json::Value`
+//! directly, see `Node::to_json_value`.
 #![cfg_attr(doc_cfg, feature(doc_cfg))]
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 extern crate alloc;
 extern crate core;
 
+#[cfg(feature = "std")]
+pub use crate::parser::parse_reader;
 pub use crate::{
     dumper::dump,
     indicator::*,
     node::*,
-    parser::{parse, parse_cyclic},
+    parser::{parse, parse_cyclic, parse_single},
     yaml::*,
 };
 
@@ -82,9 +95,9 @@ pub use crate::{
 /// use yaml_peg::{node, NodeRc};
 ///
 /// let v = vec![NodeRc::from(1), NodeRc::from(2)];
-/// assert_eq!(node!([1, 2]), v.into_iter().collect());
+/// assert_eq!(node!([1, 2]), v.into_iter().collect::<NodeRc>());
 /// let m = vec![(NodeRc::from(1), NodeRc::from(2))];
-/// assert_eq!(node!({1 => 2}), m.into_iter().collect());
+/// assert_eq!(node!({1 => 2}), m.into_iter().collect::<NodeRc>());
 /// ```
 #[macro_export]
 macro_rules! node {
@@ -122,6 +135,8 @@ macro_rules! node {
 
 pub mod dumper;
 mod indicator;
+#[cfg(feature = "json")]
+mod json;
 mod node;
 pub mod parser;
 pub mod repr;
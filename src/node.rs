@@ -1,28 +1,51 @@
 use crate::{parser::Anchors, repr::*, *};
-use alloc::string::{String, ToString};
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use core::{
+    cmp::Ordering,
     fmt::{Debug, Formatter},
     hash::{Hash, Hasher},
     iter::FromIterator,
     marker::PhantomData,
-    ops::Index,
+    ops::{Index, IndexMut},
 };
 
 macro_rules! as_method {
     {$($(#[$meta:meta])* fn $id:ident = $ty:ident$(($op:ident))?
-        $(| ($default:expr)?)?
         $(| $ty2:ident)* -> $r:ty)+} => {$(
         $(#[$meta])*
         pub fn $id(&self) -> Result<$r, u64> {
             match self.yaml() {
                 Yaml::$ty(v) $(| Yaml::$ty2(v))* => Ok(v$(.$op())?),
-                $(Yaml::Null => Ok($default),)?
                 _ => Err(self.pos),
             }
         }
     )+};
 }
 
+macro_rules! is_method {
+    {$($(#[$meta:meta])* fn $id:ident = $ty:ident)+} => {$(
+        $(#[$meta])*
+        pub fn $id(&self) -> bool {
+            matches!(self.yaml(), Yaml::$ty(_))
+        }
+    )+};
+}
+
+macro_rules! impl_eq {
+    ($(impl $($ty:ty),+ => |$n:ident, $rhs:ident| $expr:expr)+) => {
+        $($(impl<R: Repr> PartialEq<$ty> for Node<R> {
+            fn eq(&self, $rhs: &$ty) -> bool {
+                let $n = self;
+                $expr
+            }
+        })+)+
+    };
+}
+
 macro_rules! impl_iter {
     ($(impl $item:ty)+) => {
         $(impl<R: Repr> FromIterator<$item> for Node<R> {
@@ -33,6 +56,35 @@ macro_rules! impl_iter {
     };
 }
 
+/// The presentation style of the scalar in the document, which is recorded
+/// by the parser and used by the dumper.
+///
+/// The collections and the created nodes are always [`Style::Plain`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Style {
+    /// Plain scalar, the default style.
+    #[default]
+    Plain,
+    /// Single quoted string `'...'`.
+    SingleQuoted,
+    /// Double quoted string `"..."`.
+    DoubleQuoted,
+    /// Literal block string `|`.
+    Literal,
+    /// Folded block string `>`.
+    Folded,
+}
+
+/// The strategy of merging two sequences in [`Node::deep_merge`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeqMerge {
+    /// Replace the sequence with the other one, the default strategy.
+    #[default]
+    Replace,
+    /// Append the items of the other sequence.
+    Concat,
+}
+
 /// A node with [`alloc::rc::Rc`] holder.
 pub type NodeRc = Node<RcRepr>;
 /// A node with [`alloc::sync::Arc`] holder.
@@ -113,10 +165,28 @@ pub type NodeArc = Node<ArcRepr>;
 ///
 /// If you still want to copy data, use [`From`]/[`Into`] or serialize them to
 /// your custom type.
+///
+/// # Comparison
+///
+/// The nodes can be compared with the primitive types directly, the types of
+/// the value must be matched, and the integers are compared by their values.
+///
+/// ```
+/// use yaml_peg::{parse, repr::RcRepr};
+///
+/// let n = parse::<RcRepr>("{a: 0x10, b: hello, c: true, d: 1.5}").unwrap();
+/// assert!(n[0]["a"] == 16);
+/// assert!(n[0]["b"] == "hello");
+/// assert!(n[0]["c"] == true);
+/// assert!(n[0]["d"] == 1.5);
+/// assert!(n[0]["b"] != 16);
+/// ```
 pub struct Node<R: Repr> {
     pos: u64,
+    end: u64,
     tag: String,
     yaml: R::Rc,
+    style: Style,
     _marker: PhantomData<R>,
 }
 
@@ -131,11 +201,49 @@ impl<R: Repr> Node<R> {
         Self {
             yaml,
             pos,
+            end: pos,
             tag: tag.to_string(),
+            style: Style::Plain,
             _marker: PhantomData,
         }
     }
 
+    /// Create a sequence node from the items.
+    ///
+    /// ```
+    /// use yaml_peg::{node, NodeRc};
+    ///
+    /// assert_eq!(node!([1, "a"]), NodeRc::seq([node!(1), node!("a")]));
+    /// assert_eq!(node!([1, 2]), NodeRc::seq(1..=2));
+    /// ```
+    pub fn seq<I, T>(items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Self>,
+    {
+        items.into_iter().map(Into::into).collect()
+    }
+
+    /// Create a map node from the key-value pairs.
+    ///
+    /// ```
+    /// use yaml_peg::{node, NodeRc};
+    ///
+    /// let n = NodeRc::map([("a", NodeRc::seq([1, 2])), ("b", node!(()))]);
+    /// assert_eq!(node!({"a" => node!([1, 2]), "b" => ()}), n);
+    /// ```
+    pub fn map<I, K, V>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Self>,
+        V: Into<Self>,
+    {
+        pairs
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect()
+    }
+
     /// Set from existing YAML data.
     pub fn set_yaml(&mut self, yaml: impl Into<Yaml<R>>) {
         self.set_repr(R::new_rc(yaml.into()));
@@ -146,15 +254,68 @@ impl<R: Repr> Node<R> {
         self.yaml = yaml;
     }
 
+    /// Set the scalar style.
+    pub fn with_style(self, style: Style) -> Self {
+        Self { style, ..self }
+    }
+
+    /// Set the end position of the document, see [`Node::span`].
+    pub fn with_end(self, end: u64) -> Self {
+        Self { end, ..self }
+    }
+
     /// Document position.
     pub fn pos(&self) -> u64 {
         self.pos
     }
 
+    /// The span `pos..end` of the document, which is set by the parser.
+    ///
+    /// The created nodes have an empty span at their position.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parse, repr::RcRepr};
+    ///
+    /// let doc = "a: 'b c' # comment";
+    /// let n = parse::<RcRepr>(doc).unwrap().remove(0);
+    /// let (pos, end) = n["a"].span();
+    /// assert_eq!("'b c'", &doc[pos as usize..end as usize]);
+    /// assert_eq!((0, 8), n.span());
+    /// assert_eq!((0, 0), node!("a").span());
+    /// ```
+    pub fn span(&self) -> (u64, u64) {
+        (self.pos, self.end)
+    }
+
+    /// The scalar style.
+    ///
+    /// ```
+    /// use yaml_peg::{parse, repr::RcRepr, Style};
+    ///
+    /// let root = parse::<RcRepr>("[a, 'b', \"c\"]").unwrap();
+    /// let seq = root[0].as_seq().unwrap();
+    /// assert_eq!(
+    ///     seq.iter().map(|n| n.style()).collect::<Vec<_>>(),
+    ///     [Style::Plain, Style::SingleQuoted, Style::DoubleQuoted]
+    /// );
+    /// ```
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
     /// Tag. If the tag is not specified, returns a default tag from core
     /// schema.
     ///
     /// Anchor has no tag.
+    ///
+    /// ```
+    /// use yaml_peg::{parse, repr::RcRepr, Ind};
+    ///
+    /// let n = parse::<RcRepr>("[123, !!str 123, !x y]").unwrap();
+    /// assert_eq!("tag:yaml.org,2002:int", n[0][Ind(0)].tag());
+    /// assert_eq!("tag:yaml.org,2002:str", n[0][Ind(1)].tag());
+    /// assert_eq!("x", n[0][Ind(2)].tag());
+    /// ```
     pub fn tag(&self) -> &str {
         match self.tag.as_str() {
             "" => match self.yaml() {
@@ -163,6 +324,8 @@ impl<R: Repr> Node<R> {
                 Yaml::Int(_) => concat!(parser::tag_prefix!(), "int"),
                 Yaml::Float(_) => concat!(parser::tag_prefix!(), "float"),
                 Yaml::Str(_) => concat!(parser::tag_prefix!(), "str"),
+                Yaml::Timestamp(_) => concat!(parser::tag_prefix!(), "timestamp"),
+                Yaml::Bytes(_) => concat!(parser::tag_prefix!(), "binary"),
                 Yaml::Seq(_) => concat!(parser::tag_prefix!(), "seq"),
                 Yaml::Map(_) => concat!(parser::tag_prefix!(), "map"),
                 Yaml::Alias(_) => "",
@@ -176,6 +339,33 @@ impl<R: Repr> Node<R> {
         &self.yaml
     }
 
+    /// Mutable YAML data.
+    ///
+    /// The data will be cloned if it is shared with other nodes,
+    /// so the other nodes will not be changed.
+    ///
+    /// ```
+    /// use yaml_peg::{node, Yaml};
+    ///
+    /// let mut n = node!([1]);
+    /// let n2 = n.clone();
+    /// if let Yaml::Seq(v) = n.yaml_mut() {
+    ///     v.push(node!(2));
+    /// }
+    /// assert_eq!(n, node!([1, 2]));
+    /// assert_eq!(n2, node!([1]));
+    /// ```
+    pub fn yaml_mut(&mut self) -> &mut Yaml<R> {
+        R::make_mut(&mut self.yaml)
+    }
+
+    /// Take the YAML data out of the node.
+    ///
+    /// The data will be cloned only if it is shared with other nodes.
+    pub fn into_yaml(self) -> Yaml<R> {
+        R::into_inner(self.yaml)
+    }
+
     /// Clone YAML repr.
     pub fn clone_yaml(&self) -> R::Rc {
         self.yaml.clone()
@@ -202,17 +392,66 @@ impl<R: Repr> Node<R> {
         &self.yaml
     }
 
+    /// Return true if the two nodes hold the same data allocation.
+    ///
+    /// The aliases of the [`parse`] function are shared with their anchors.
+    ///
+    /// ```
+    /// use yaml_peg::{parse, repr::RcRepr, Ind};
+    ///
+    /// let n = parse::<RcRepr>("- &a [1, 2]\n- *a\n- [1, 2]").unwrap();
+    /// assert!(n[0][Ind(0)].is_shared(&n[0][Ind(1)]));
+    /// assert!(!n[0][Ind(0)].is_shared(&n[0][Ind(2)]));
+    /// ```
+    pub fn is_shared(&self, rhs: &Self) -> bool {
+        core::ptr::eq(self.yaml(), rhs.yaml())
+    }
+
     /// Check the value is null.
     pub fn is_null(&self) -> bool {
         *self.yaml() == Yaml::Null
     }
 
+    is_method! {
+        /// Check the value is boolean.
+        ///
+        /// ```
+        /// use yaml_peg::node;
+        ///
+        /// assert!(node!(true).is_bool());
+        /// assert!(!node!("true").is_bool());
+        /// ```
+        fn is_bool = Bool
+        /// Check the value is integer.
+        fn is_int = Int
+        /// Check the value is float.
+        fn is_float = Float
+        /// Check the value is string.
+        fn is_str = Str
+        /// Check the value is timestamp.
+        fn is_timestamp = Timestamp
+        /// Check the value is binary.
+        fn is_bytes = Bytes
+        /// Check the value is sequence.
+        fn is_seq = Seq
+        /// Check the value is map.
+        fn is_map = Map
+        /// Check the value is alias.
+        fn is_alias = Alias
+    }
+
     /// Convert to integer.
     ///
+    /// The hexadecimal (`0x`), octal (`0o`) and binary (`0b`) notations are
+    /// supported.
+    ///
     /// ```
-    /// use yaml_peg::node;
+    /// use yaml_peg::{node, parse, Ind};
     ///
     /// assert_eq!(60, node!(60).as_int().unwrap());
+    /// let n = parse("[0xFF, 0xab, 0o17, 0b1010]").unwrap().remove(0);
+    /// assert_eq!(node!([255, 171, 15, 10]), n);
+    /// assert_eq!(255, n[Ind(0)].as_int().unwrap());
     /// ```
     pub fn as_int(&self) -> Result<i64, u64> {
         match self.yaml() {
@@ -223,10 +462,18 @@ impl<R: Repr> Node<R> {
 
     /// Convert to float.
     ///
+    /// The digit separators `_` and the special values `.inf`, `+.inf`,
+    /// `-.inf` and `.nan` are supported. The spellings without the dot like
+    /// `inf` are strings in the YAML 1.2 core schema, unless the loader
+    /// enables [`Loader::undotted_float`](crate::parser::Loader::undotted_float).
+    ///
     /// ```
-    /// use yaml_peg::node;
+    /// use yaml_peg::{node, parse, repr::RcRepr, Ind};
     ///
     /// assert_eq!(20.06, node!(20.06).as_float().unwrap());
+    /// let n = parse::<RcRepr>("[1_000.5, -.inf]").unwrap().remove(0);
+    /// assert_eq!(1000.5, n[Ind(0)].as_float().unwrap());
+    /// assert_eq!(f64::NEG_INFINITY, n[Ind(1)].as_float().unwrap());
     /// ```
     pub fn as_float(&self) -> Result<f64, u64> {
         match self.yaml() {
@@ -263,16 +510,38 @@ impl<R: Repr> Node<R> {
 
         /// Convert to string pointer.
         ///
-        /// This method allows null, it represented as empty string.
-        /// You can check them by [`str::is_empty`].
+        /// Only the string type is accepted, null is not an empty string.
+        /// Use [`Node::as_value`] to accept other scalars.
         ///
         /// ```
         /// use yaml_peg::node;
         ///
         /// assert_eq!("abc", node!("abc").as_str().unwrap());
-        /// assert!(node!(()).as_str().unwrap().is_empty());
+        /// assert!(node!(()).as_str().is_err());
+        /// assert!(node!(1).as_str().is_err());
+        /// ```
+        fn as_str = Str -> &str
+
+        /// Convert to the string of timestamp.
+        ///
+        /// ```
+        /// use yaml_peg::{parse, repr::RcRepr};
+        ///
+        /// let n = parse::<RcRepr>("2001-12-14t21:59:43.10-05:00").unwrap();
+        /// assert_eq!("2001-12-14t21:59:43.10-05:00", n[0].as_timestamp().unwrap());
         /// ```
-        fn as_str = Str | ("")? -> &str
+        fn as_timestamp = Timestamp -> &str
+
+        /// Convert to the binary data, which is decoded from the base64
+        /// string with the `!!binary` tag.
+        ///
+        /// ```
+        /// use yaml_peg::{parse, repr::RcRepr};
+        ///
+        /// let n = parse::<RcRepr>("!!binary aGVsbG8=").unwrap();
+        /// assert_eq!(b"hello", n[0].as_bytes().unwrap());
+        /// ```
+        fn as_bytes = Bytes -> &[u8]
 
         /// Convert to sequence.
         ///
@@ -302,6 +571,177 @@ impl<R: Repr> Node<R> {
         fn as_map = Map(clone) -> Map<R>
     }
 
+    /// Convert to the owned string.
+    ///
+    /// Same as [`Node::as_str`], null is not allowed.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// assert_eq!("abc", node!("abc").into_string().unwrap());
+    /// assert_eq!(Err(0), node!(()).into_string());
+    /// ```
+    pub fn into_string(self) -> Result<String, u64> {
+        let pos = self.pos;
+        match self.into_yaml() {
+            Yaml::Str(s) => Ok(s),
+            _ => Err(pos),
+        }
+    }
+
+    /// Clone the items of the sequence into a vector, which is same as
+    /// [`Node::as_seq`], but the other kinds are not errors.
+    ///
+    /// + The null is an empty vector.
+    /// + The other kinds, including the maps, are the one-element vector of
+    ///   the node itself.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// assert_eq!(vec![node!(1), node!(2)], node!([1, 2]).as_vec());
+    /// assert_eq!(vec![node!("a")], node!("a").as_vec());
+    /// assert!(node!(()).as_vec().is_empty());
+    /// ```
+    pub fn as_vec(&self) -> Seq<R> {
+        match self.yaml() {
+            Yaml::Seq(v) => v.clone(),
+            Yaml::Null => Vec::new(),
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// Convert to the owned sequence.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// assert_eq!(vec![node!(1)], node!([1]).into_seq().unwrap());
+    /// assert_eq!(Err(0), node!({}).into_seq());
+    /// ```
+    pub fn into_seq(self) -> Result<Seq<R>, u64> {
+        let pos = self.pos;
+        match self.into_yaml() {
+            Yaml::Seq(v) => Ok(v),
+            _ => Err(pos),
+        }
+    }
+
+    /// Convert to the owned map.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// let m = node!({1 => 2}).into_map().unwrap();
+    /// assert_eq!(node!(2), m[&node!(1)]);
+    /// assert_eq!(Err(0), node!([]).into_map().map(|_| ()));
+    /// ```
+    pub fn into_map(self) -> Result<Map<R>, u64> {
+        let pos = self.pos;
+        match self.into_yaml() {
+            Yaml::Map(m) => Ok(m),
+            _ => Err(pos),
+        }
+    }
+
+    /// Iterate over the key-value pairs of the map by reference, in the
+    /// insertion order.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// let n = node!({"b" => 1, "a" => 2});
+    /// let keys = n.entries().unwrap().map(|(k, _)| k.as_str().unwrap());
+    /// assert_eq!(keys.collect::<Vec<_>>(), ["b", "a"]);
+    /// assert_eq!(Err(0), node!([1]).entries().map(|_| ()));
+    /// ```
+    pub fn entries(&self) -> Result<impl Iterator<Item = (&Self, &Self)>, u64> {
+        match self.yaml() {
+            Yaml::Map(m) => Ok(m.iter()),
+            _ => Err(self.pos),
+        }
+    }
+
+    /// Iterate over the items of the set, which is a map with `!!set` tag.
+    /// The values of the set are ignored.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parse, repr::RcRepr};
+    ///
+    /// let root = parse::<RcRepr>("!!set {a, b}").unwrap();
+    /// let items = root[0].as_set().unwrap().collect::<Vec<_>>();
+    /// assert_eq!(items, [&node!("a"), &node!("b")]);
+    /// assert_eq!(Err(0), node!({"a" => ()}).as_set().map(|_| ()));
+    /// ```
+    pub fn as_set(&self) -> Result<impl Iterator<Item = &Self>, u64> {
+        match self.yaml() {
+            Yaml::Map(m) if self.tag() == concat!(parser::tag_prefix!(), "set") => Ok(m.keys()),
+            _ => Err(self.pos),
+        }
+    }
+
+    /// Get the key-value pairs of the ordered map, which is a sequence with
+    /// `!!omap` tag, and each item is a map with single pair.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parse, repr::RcRepr};
+    ///
+    /// let root = parse::<RcRepr>("!!omap\n- b: 1\n- a: 2").unwrap();
+    /// let pairs = root[0].as_omap().unwrap();
+    /// assert_eq!(pairs, [(&node!("b"), &node!(1)), (&node!("a"), &node!(2))]);
+    /// assert_eq!(Err(0), node!([node!({"a" => 1})]).as_omap());
+    /// ```
+    pub fn as_omap(&self) -> Result<Vec<(&Self, &Self)>, u64> {
+        match self.yaml() {
+            Yaml::Seq(v) if self.tag() == concat!(parser::tag_prefix!(), "omap") => v
+                .iter()
+                .map(|n| match n.yaml() {
+                    Yaml::Map(m) if m.len() == 1 => Ok(m.iter().next().unwrap()),
+                    _ => Err(n.pos),
+                })
+                .collect(),
+            _ => Err(self.pos),
+        }
+    }
+
+    /// The length of the data.
+    ///
+    /// + Sequence: the number of items.
+    /// + Map: the number of key-value pairs.
+    /// + String: the number of characters.
+    /// + Other types: zero.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// assert_eq!(3, node!([1, 2, 3]).len());
+    /// assert_eq!(1, node!({"a" => "b"}).len());
+    /// assert_eq!(2, node!("中文").len());
+    /// assert_eq!(0, node!(123).len());
+    /// ```
+    pub fn len(&self) -> usize {
+        match self.yaml() {
+            Yaml::Seq(v) => v.len(),
+            Yaml::Map(m) => m.len(),
+            Yaml::Str(s) => s.chars().count(),
+            _ => 0,
+        }
+    }
+
+    /// Return true if the [`Node::len`] is zero.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// assert!(node!({}).is_empty());
+    /// assert!(node!("").is_empty());
+    /// assert!(node!(()).is_empty());
+    /// assert!(!node!([()]).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Convert to string pointer for string, null, bool, int, and float type.
     ///
     /// This method is useful when the option mixed with digit values.
@@ -318,7 +758,7 @@ impl<R: Repr> Node<R> {
     /// ```
     pub fn as_value(&self) -> Result<&str, u64> {
         match self.yaml() {
-            Yaml::Str(s) | Yaml::Int(s) | Yaml::Float(s) => Ok(s),
+            Yaml::Str(s) | Yaml::Int(s) | Yaml::Float(s) | Yaml::Timestamp(s) => Ok(s),
             Yaml::Bool(true) => Ok("true"),
             Yaml::Bool(false) => Ok("false"),
             Yaml::Null => Ok(""),
@@ -346,16 +786,251 @@ impl<R: Repr> Node<R> {
         }
     }
 
+    /// Replace all the aliases with the referenced nodes from `anchors`
+    /// recursively, returns a new node.
+    ///
+    /// This method is used with the result of the [`parse_cyclic`] function,
+    /// the [`parse`] function has already replaced the anchors.
+    /// If the anchor is undefined or referenced by itself (cyclic data),
+    /// return `Err` with the alias position.
+    ///
+    /// Each anchor is resolved only once, so the aliases of the same anchor
+    /// share the data, see [`Node::is_shared`]. The subtrees without any
+    /// alias are shared with the original node as well.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parse_cyclic};
+    ///
+    /// let (root, anchors) = parse_cyclic("- &a 1\n- *a").unwrap();
+    /// assert_eq!(node!([1, node!(*"a")]), root[0]);
+    /// let n = root[0].resolve_anchors(&anchors[0]).unwrap();
+    /// assert_eq!(node!([1, 1]), n);
+    /// let (root, anchors) = parse_cyclic("&a [*a]").unwrap();
+    /// assert_eq!(node!([node!(*"a")]), root[0]);
+    /// assert_eq!(Err(4), root[0].resolve_anchors(&anchors[0]));
+    /// ```
+    pub fn resolve_anchors(&self, anchors: &Anchors<R>) -> Result<Self, u64> {
+        self.resolve_anchors_inner(anchors, &mut Vec::new(), &mut Anchors::new())
+    }
+
+    fn resolve_anchors_inner<'a>(
+        &self,
+        anchors: &'a Anchors<R>,
+        visited: &mut Vec<&'a str>,
+        resolved: &mut Anchors<R>,
+    ) -> Result<Self, u64> {
+        let yaml: Yaml<R> = match self.yaml() {
+            Yaml::Alias(a) => {
+                let n = match resolved.get(a) {
+                    Some(n) => n.clone(),
+                    None => {
+                        let (a, n) = anchors.get_key_value(a).ok_or(self.pos)?;
+                        if visited.contains(&a.as_str()) {
+                            return Err(self.pos);
+                        }
+                        visited.push(a);
+                        let n = n.resolve_anchors_inner(anchors, visited, resolved)?;
+                        visited.pop();
+                        resolved.insert(a.clone(), n.clone());
+                        n
+                    }
+                };
+                let n = Self::new_repr(n.yaml, self.pos, n.tag).with_style(n.style);
+                return Ok(n.with_end(self.end));
+            }
+            Yaml::Seq(v) => {
+                let s = v
+                    .iter()
+                    .map(|n| n.resolve_anchors_inner(anchors, visited, resolved))
+                    .collect::<Result<Seq<R>, _>>()?;
+                if s.iter().zip(v).all(|(a, b)| a.is_shared(b)) {
+                    return Ok(self.clone());
+                }
+                Yaml::Seq(s)
+            }
+            Yaml::Map(m) => {
+                let map = m
+                    .iter()
+                    .map(|(k, v)| {
+                        let k = k.resolve_anchors_inner(anchors, visited, resolved)?;
+                        let v = v.resolve_anchors_inner(anchors, visited, resolved)?;
+                        Ok((k, v))
+                    })
+                    .collect::<Result<Map<R>, u64>>()?;
+                let same = map
+                    .iter()
+                    .zip(m)
+                    .all(|((k1, v1), (k2, v2))| k1.is_shared(k2) && v1.is_shared(v2));
+                if same {
+                    return Ok(self.clone());
+                }
+                Yaml::Map(map)
+            }
+            _ => return Ok(self.clone()),
+        };
+        Ok(Self::new(yaml, self.pos, &self.tag).with_end(self.end))
+    }
+
+    /// Rewrite the node recursively by the function.
+    ///
+    /// The traversal is bottom-up: the items of the sequences and the values
+    /// of the maps are transformed before their parent, in the document
+    /// order. The map keys are not transformed. Each node is passed with its
+    /// original position and tag.
+    ///
+    /// ```
+    /// use yaml_peg::{node, NodeRc};
+    ///
+    /// let n = node!({"a" => node!([1, 2]), "b" => 3});
+    /// let n = n.transform(|n: NodeRc| match n.as_int() {
+    ///     Ok(i) => node!(i * 10),
+    ///     Err(_) => n,
+    /// });
+    /// assert_eq!(node!({"a" => node!([10, 20]), "b" => 30}), n);
+    /// ```
+    pub fn transform<F>(self, mut f: F) -> Self
+    where
+        F: FnMut(Self) -> Self,
+    {
+        self.transform_inner(&mut f)
+    }
+
+    fn transform_inner<F>(self, f: &mut F) -> Self
+    where
+        F: FnMut(Self) -> Self,
+    {
+        let Self { pos, end, tag, yaml, style, .. } = self;
+        let yaml: Yaml<R> = match R::into_inner(yaml) {
+            Yaml::Seq(v) => v.into_iter().map(|n| n.transform_inner(f)).collect(),
+            Yaml::Map(m) => m
+                .into_iter()
+                .map(|(k, v)| (k, v.transform_inner(f)))
+                .collect(),
+            yaml => yaml,
+        };
+        f(Self::new(yaml, pos, tag).with_style(style).with_end(end))
+    }
+
+    /// Merge the other node into this node recursively, such as applying the
+    /// overrides to the defaults.
+    ///
+    /// The items of two maps are merged by their keys, the new keys are
+    /// appended to the end. Two sequences are merged by the strategy `seq`.
+    /// Otherwise, this node is replaced by the other one.
+    ///
+    /// This is different from the merge key `<<` of YAML, which is handled
+    /// by the parser.
+    ///
+    /// ```
+    /// use yaml_peg::{node, SeqMerge};
+    ///
+    /// let mut n = node!({"a" => node!({"b" => 1, "c" => 2}), "d" => node!([1])});
+    /// let rhs = node!({"a" => node!({"c" => 3}), "d" => node!([2])});
+    /// n.deep_merge(&rhs, SeqMerge::Replace);
+    /// assert_eq!(n, node!({"a" => node!({"b" => 1, "c" => 3}), "d" => node!([2])}));
+    /// n.deep_merge(&rhs, SeqMerge::Concat);
+    /// assert_eq!(n, node!({"a" => node!({"b" => 1, "c" => 3}), "d" => node!([2, 2])}));
+    /// ```
+    pub fn deep_merge(&mut self, other: &Self, seq: SeqMerge) {
+        match (self.yaml(), other.yaml()) {
+            (Yaml::Map(_), Yaml::Map(rhs)) => {
+                if let Yaml::Map(m) = self.yaml_mut() {
+                    for (k, v) in rhs {
+                        match m.get_mut(k) {
+                            Some(n) => n.deep_merge(v, seq),
+                            None => {
+                                m.insert(k.clone(), v.clone());
+                            }
+                        }
+                    }
+                }
+            }
+            (Yaml::Seq(_), Yaml::Seq(rhs)) if seq == SeqMerge::Concat => {
+                if let Yaml::Seq(s) = self.yaml_mut() {
+                    s.extend(rhs.iter().cloned());
+                }
+            }
+            _ => *self = other.clone(),
+        }
+    }
+
+    /// Replace the `${name}` patterns in the strings recursively, the
+    /// values are given by the function.
+    ///
+    /// If the function returns `None`, the pattern is kept, or return `Err`
+    /// with the string position if `strict` is true. The map keys are not
+    /// interpolated.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// let mut n = node!({"path" => "${HOME}/bin", "user" => "${USER}"});
+    /// let f = |name: &str| (name == "HOME").then(|| "/home/a".to_string());
+    /// n.interpolate(f, false).unwrap();
+    /// assert_eq!(n, node!({"path" => "/home/a/bin", "user" => "${USER}"}));
+    /// assert_eq!(Err(0), n.interpolate(f, true));
+    /// ```
+    pub fn interpolate<F>(&mut self, mut f: F, strict: bool) -> Result<(), u64>
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        self.interpolate_inner(&mut f, strict)
+    }
+
+    fn interpolate_inner<F>(&mut self, f: &mut F, strict: bool) -> Result<(), u64>
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let pos = self.pos;
+        match self.yaml() {
+            Yaml::Str(s) if s.contains("${") => {}
+            Yaml::Seq(_) | Yaml::Map(_) => {}
+            _ => return Ok(()),
+        }
+        match self.yaml_mut() {
+            Yaml::Str(s) => {
+                let mut v = String::new();
+                let mut rest = s.as_str();
+                while let Some(start) = rest.find("${") {
+                    let Some(end) = rest[start..].find('}') else {
+                        break;
+                    };
+                    v += &rest[..start];
+                    match f(&rest[start + 2..start + end]) {
+                        Some(value) => v += &value,
+                        None if strict => return Err(pos),
+                        None => v += &rest[start..=start + end],
+                    }
+                    rest = &rest[start + end + 1..];
+                }
+                v += rest;
+                *s = v;
+            }
+            Yaml::Seq(v) => v
+                .iter_mut()
+                .try_for_each(|n| n.interpolate_inner(f, strict))?,
+            Yaml::Map(m) => m
+                .iter_mut()
+                .try_for_each(|(_, n)| n.interpolate_inner(f, strict))?,
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Convert to map and try to get the value by key.
     ///
     /// If any key is missing, return `Err` with node position.
+    /// The indexing operator will panic in that case instead.
+    /// The number keys are matched by their values.
     ///
     /// ```
     /// # fn main() -> Result<(), u64> {
-    /// use yaml_peg::node;
+    /// use yaml_peg::{node, parse, repr::RcRepr};
     ///
     /// let n = node!({node!("a") => node!({node!("b") => node!(30.)})});
     /// assert_eq!(&node!(30.), n.get("a")?.get("b")?);
+    /// let n = parse::<RcRepr>("{0x10: a}").unwrap().remove(0);
+    /// assert_eq!(&node!("a"), n.get(16)?);
     /// # Ok::<(), u64>(()) }
     /// ```
     pub fn get<Y: Into<Self>>(&self, key: Y) -> Result<&Self, u64> {
@@ -366,6 +1041,36 @@ impl<R: Repr> Node<R> {
         }
     }
 
+    /// Return true if the node is a map and contains the key.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// let n = node!({"a" => 1, 2 => 3});
+    /// assert!(n.contains_key("a"));
+    /// assert!(n.contains_key(2));
+    /// assert!(!n.contains_key("b"));
+    /// assert!(!node!(["a"]).contains_key("a"));
+    /// ```
+    pub fn contains_key<Y: Into<Self>>(&self, key: Y) -> bool {
+        matches!(self.yaml(), Yaml::Map(m) if m.contains_key(&key.into()))
+    }
+
+    /// Return true if the node is a sequence and contains the item.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// let n = node!(["a", 1]);
+    /// assert!(n.contains("a"));
+    /// assert!(n.contains(1));
+    /// assert!(!n.contains("b"));
+    /// assert!(!node!({"a" => 1}).contains("a"));
+    /// ```
+    pub fn contains<Y: Into<Self>>(&self, item: Y) -> bool {
+        matches!(self.yaml(), Yaml::Seq(v) if v.contains(&item.into()))
+    }
+
     /// Same as [`Node::get`] but provide default value if the key is missing.
     /// For this method, a transform method `as_*` is required.
     ///
@@ -401,7 +1106,7 @@ impl<R: Repr> Node<R> {
     ///
     /// let n = node!({node!("a") => node!([node!(1), node!(2), node!(3)])});
     /// let a = n.get_default("c", vec![], Node::as_seq)?;
-    /// assert_eq!(a, vec![]);
+    /// assert!(a.is_empty());
     /// # Ok::<(), u64>(()) }
     /// ```
     pub fn get_default<'a, Y, Ret, F>(
@@ -425,6 +1130,77 @@ impl<R: Repr> Node<R> {
         }
     }
 
+    /// Return the default node if this node is null, otherwise return itself.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// let cfg = node!({"timeout" => (), "retry" => 3});
+    /// let default = node!(30);
+    /// assert_eq!(&node!(30), cfg["timeout"].or(&default));
+    /// assert_eq!(&node!(3), cfg["retry"].or(&default));
+    /// ```
+    pub fn or<'a>(&'a self, default: &'a Self) -> &'a Self {
+        if self.is_null() {
+            default
+        } else {
+            self
+        }
+    }
+
+    /// Get the value by key, return the default node if the key is missing,
+    /// the value is null, or this node is not a map.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// let cfg = node!({"timeout" => (), "retry" => 3});
+    /// let default = node!(30);
+    /// assert_eq!(&node!(30), cfg.get_or("timeout", &default));
+    /// assert_eq!(&node!(30), cfg.get_or("delay", &default));
+    /// assert_eq!(&node!(3), cfg.get_or("retry", &default));
+    /// ```
+    pub fn get_or<'a, Y: Into<Self>>(&'a self, key: Y, default: &'a Self) -> &'a Self {
+        self.get(key).map_or(default, |n| n.or(default))
+    }
+
+    /// Get the value of the value key `=`, which is the default value of the
+    /// map in some schemas, return `None` if missing or not a map.
+    ///
+    /// ```
+    /// use yaml_peg::{parse, repr::RcRepr};
+    ///
+    /// let n = parse::<RcRepr>("=: 10\nmax: 20").unwrap();
+    /// assert_eq!(Ok(10), n[0].default_value().unwrap().as_int());
+    /// assert!(n[0]["max"].default_value().is_none());
+    /// ```
+    pub fn default_value(&self) -> Option<&Self> {
+        self.get("=").ok()
+    }
+
+    /// Same as [`Node::get`] but returns the mutable reference.
+    ///
+    /// The order of the map will be kept after modification.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), u64> {
+    /// use yaml_peg::node;
+    ///
+    /// let mut n = node!({"a" => 1, "b" => 2});
+    /// *n.get_mut("a")? = node!("c");
+    /// assert_eq!(n, node!({"a" => "c", "b" => 2}));
+    /// assert_eq!(Err(0), n.get_mut("d").map(|_| ()));
+    /// # Ok::<(), u64>(()) }
+    /// ```
+    pub fn get_mut<Y: Into<Self>>(&mut self, key: Y) -> Result<&mut Self, u64> {
+        let pos = self.pos;
+        if let Yaml::Map(m) = self.yaml_mut() {
+            m.get_mut(&key.into()).ok_or(pos)
+        } else {
+            Err(pos)
+        }
+    }
+
     /// Get node through index indicator. Only suitable for sequence.
     ///
     /// ```
@@ -442,11 +1218,210 @@ impl<R: Repr> Node<R> {
             Err(self.pos)
         }
     }
+
+    /// Same as [`Node::get_ind`] but returns the mutable reference.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), u64> {
+    /// use yaml_peg::{node, Ind};
+    ///
+    /// let mut n = node!([node!("a"), node!("b")]);
+    /// *n.get_ind_mut(Ind(1))? = node!("c");
+    /// assert_eq!(n, node!(["a", "c"]));
+    /// # Ok::<(), u64>(()) }
+    /// ```
+    pub fn get_ind_mut(&mut self, ind: Ind) -> Result<&mut Self, u64> {
+        let pos = self.pos;
+        if let Yaml::Seq(v) = self.yaml_mut() {
+            v.get_mut(ind.0).ok_or(pos)
+        } else {
+            Err(pos)
+        }
+    }
+
+    /// Move the value out by key and leave a null in its place, the key is
+    /// kept. Return `Err` with node position if the key is missing.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), u64> {
+    /// use yaml_peg::node;
+    ///
+    /// let mut n = node!({"a" => node!([1, 2]), "b" => 3});
+    /// assert_eq!(node!([1, 2]), n.take("a")?);
+    /// assert_eq!(node!({"a" => (), "b" => 3}), n);
+    /// # Ok::<(), u64>(()) }
+    /// ```
+    pub fn take<Y: Into<Self>>(&mut self, key: Y) -> Result<Self, u64> {
+        self.get_mut(key).map(Self::take_inner)
+    }
+
+    /// Same as [`Node::take`] but through index indicator. Only suitable for
+    /// sequence.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), u64> {
+    /// use yaml_peg::{node, Ind};
+    ///
+    /// let mut n = node!([1, 2]);
+    /// assert_eq!(node!(2), n.take_ind(Ind(1))?);
+    /// assert_eq!(node!([1, ()]), n);
+    /// # Ok::<(), u64>(()) }
+    /// ```
+    pub fn take_ind(&mut self, ind: Ind) -> Result<Self, u64> {
+        self.get_ind_mut(ind).map(Self::take_inner)
+    }
+
+    fn take_inner(&mut self) -> Self {
+        let null = Self::new(Yaml::Null, self.pos, "").with_end(self.pos);
+        core::mem::replace(self, null)
+    }
+
+    /// Get the node through the path of keys, the numeric segments are used
+    /// as indexes when the current node is a sequence, and they are also
+    /// matched with the integer keys of the maps.
+    ///
+    /// If any segment is missing, return `Err` with the position of the node
+    /// that is being indexed.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), u64> {
+    /// use yaml_peg::node;
+    ///
+    /// let n = node!({"items" => node!([node!({"name" => "a"}), node!({"0" => "b", 1 => "c"})])});
+    /// assert_eq!(&node!("a"), n.get_path(&["items", "0", "name"])?);
+    /// assert_eq!(&node!("b"), n.get_path(&["items", "1", "0"])?);
+    /// assert_eq!(&node!("c"), n.get_path(&["items", "1", "1"])?);
+    /// assert!(n.get_path(&["items", "x"]).is_err());
+    /// # Ok::<(), u64>(()) }
+    /// ```
+    pub fn get_path(&self, path: &[&str]) -> Result<&Self, u64> {
+        path.iter().try_fold(self, |n, seg| match n.yaml() {
+            Yaml::Seq(_) => n.get_ind(Ind(seg.parse().map_err(|_| n.pos)?)),
+            _ => n
+                .get(*seg)
+                .or_else(|e| seg.parse::<i128>().map_or(Err(e), |i| n.get(i))),
+        })
+    }
+
+    /// Query the nodes by a JSONPath-like path, return all matched nodes.
+    ///
+    /// The path starts with an optional root `$`, then the segments:
+    ///
+    /// + `.key` or `['key']`: the value of the string key in the map.
+    /// + `[n]`: the n-th item of the sequence.
+    /// + `.*` or `[*]`: all items of the sequence or all values of the map.
+    ///
+    /// Missing segments and invalid paths match nothing.
+    ///
+    /// ```
+    /// use yaml_peg::node;
+    ///
+    /// let n = node!({"items" => node!([
+    ///     node!({"name" => "a", "tags" => node!(["x", "y"])}),
+    ///     node!({"name" => "b"}),
+    /// ])});
+    /// assert_eq!(n.query("$.items[*].name"), [&node!("a"), &node!("b")]);
+    /// assert_eq!(n.query("$.items[0].tags[1]"), [&node!("y")]);
+    /// assert!(n.query("$.items[2]").is_empty());
+    /// ```
+    pub fn query(&self, path: &str) -> Vec<&Self> {
+        let mut path = path.strip_prefix('$').unwrap_or(path);
+        let mut nodes = vec![self];
+        while !path.is_empty() {
+            let seg = if let Some(s) = path.strip_prefix('.') {
+                let end = s.find(['.', '[']).unwrap_or(s.len());
+                path = &s[end..];
+                &s[..end]
+            } else if let Some((seg, s)) = path.strip_prefix('[').and_then(|s| s.split_once(']')) {
+                path = s;
+                match seg.parse() {
+                    Ok(i) => {
+                        nodes = nodes
+                            .into_iter()
+                            .filter_map(|n| n.get_ind(Ind(i)).ok())
+                            .collect();
+                        continue;
+                    }
+                    Err(_) => seg
+                        .strip_prefix('\'')
+                        .and_then(|s| s.strip_suffix('\''))
+                        .unwrap_or(seg),
+                }
+            } else {
+                return Vec::new();
+            };
+            nodes = nodes
+                .into_iter()
+                .flat_map(|n| -> Vec<&Self> {
+                    match n.yaml() {
+                        Yaml::Seq(v) if seg == "*" => v.iter().collect(),
+                        Yaml::Map(m) if seg == "*" => m.values().collect(),
+                        _ => n.get(seg).into_iter().collect(),
+                    }
+                })
+                .collect();
+        }
+        nodes
+    }
 }
 
 impl<R: Repr> Debug for Node<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "Node{:?}", &self.yaml)
+        if f.alternate() {
+            self.fmt_pretty(f, 0)
+        } else {
+            write!(f, "Node{:?}", &self.yaml)
+        }
+    }
+}
+
+impl<R: Repr> Node<R> {
+    /// The alternate debug format, the items are indented line by line, and
+    /// the position and tag are shown if not default.
+    fn fmt_pretty(&self, f: &mut Formatter<'_>, level: usize) -> core::fmt::Result {
+        match self.yaml() {
+            Yaml::Seq(_) => f.write_str("NodeSeq")?,
+            Yaml::Map(_) => f.write_str("NodeMap")?,
+            yaml => write!(f, "Node{:?}", yaml)?,
+        }
+        if self.pos != 0 {
+            write!(f, " @{}", self.pos)?;
+        }
+        match self.tag.strip_prefix(parser::tag_prefix!()) {
+            _ if self.tag.is_empty() => {}
+            Some(tag) => write!(f, " !!{}", tag)?,
+            None => write!(f, " !<{}>", self.tag)?,
+        }
+        let w = 4 * (level + 1);
+        match self.yaml() {
+            Yaml::Seq(s) => {
+                f.write_str(" [")?;
+                for n in s {
+                    write!(f, "\n{:w$}", "")?;
+                    n.fmt_pretty(f, level + 1)?;
+                    f.write_str(",")?;
+                }
+                if !s.is_empty() {
+                    write!(f, "\n{:1$}", "", w - 4)?;
+                }
+                f.write_str("]")
+            }
+            Yaml::Map(m) => {
+                f.write_str(" {")?;
+                for (k, v) in m {
+                    write!(f, "\n{:w$}", "")?;
+                    k.fmt_pretty(f, level + 1)?;
+                    f.write_str(": ")?;
+                    v.fmt_pretty(f, level + 1)?;
+                    f.write_str(",")?;
+                }
+                if !m.is_empty() {
+                    write!(f, "\n{:1$}", "", w - 4)?;
+                }
+                f.write_str("}")
+            }
+            _ => Ok(()),
+        }
     }
 }
 
@@ -474,6 +1449,27 @@ impl<R: Repr> PartialEq for Node<R> {
 
 impl<R: Repr> Eq for Node<R> {}
 
+impl_eq! {
+    impl () => |n, _rhs| matches!(n.yaml(), Yaml::Null)
+    impl bool => |n, rhs| n.as_bool() == Ok(*rhs)
+    impl str, &str, String => |n, rhs| matches!(n.yaml(), Yaml::Str(s) if s == rhs)
+    impl usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128 => |n, rhs| n.is_int()
+        && *n.yaml() == Yaml::from(*rhs)
+    impl f32, f64 => |n, rhs| n.is_float() && *n.yaml() == Yaml::from(*rhs)
+}
+
+impl<R: Repr> PartialOrd for Node<R> {
+    fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl<R: Repr> Ord for Node<R> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        self.yaml.cmp(&rhs.yaml)
+    }
+}
+
 /// Indicator of the node use to index the sequence position.
 pub struct Ind(pub usize);
 
@@ -489,6 +1485,16 @@ impl<R: Repr> Index<Ind> for Node<R> {
     }
 }
 
+impl<R: Repr> IndexMut<Ind> for Node<R> {
+    fn index_mut(&mut self, index: Ind) -> &mut Self::Output {
+        if let Yaml::Seq(v) = self.yaml_mut() {
+            v.index_mut(index.0)
+        } else {
+            panic!("out of bound!")
+        }
+    }
+}
+
 impl<R, I> Index<I> for Node<R>
 where
     R: Repr,
@@ -506,6 +1512,21 @@ where
     }
 }
 
+impl<R, I> IndexMut<I> for Node<R>
+where
+    R: Repr,
+    I: Into<Self>,
+{
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        if let Yaml::Map(m) = self.yaml_mut() {
+            m.get_mut(&index.into())
+                .unwrap_or_else(|| panic!("out of bound!"))
+        } else {
+            panic!("out of bound!")
+        }
+    }
+}
+
 impl<R, Y> From<Y> for Node<R>
 where
     R: Repr,
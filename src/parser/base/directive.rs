@@ -20,13 +20,27 @@ impl Parser<'_> {
 
     fn directive_yaml(&mut self) -> PResult<()> {
         self.ws(TakeOpt::More(1))?;
-        if self.version_checked {
-            self.err("checked version")
-        } else if !self.context(|p| p.sym_seq(b"1.1").is_ok() || p.sym_seq(b"1.2").is_ok()) {
-            self.err("invalid version")
-        } else {
-            self.version_checked = true;
-            Ok(())
+        if self.version.is_some() {
+            return self.err("checked version");
+        }
+        self.forward();
+        self.take_while(Self::not_in(b" \t\n\r"), TakeOpt::More(0))?;
+        let version = self.text();
+        let version = version
+            .split_once('.')
+            .filter(|(major, minor)| {
+                [major, minor]
+                    .iter()
+                    .all(|s| !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit()))
+            })
+            .and_then(|(major, minor)| Some((major.parse().ok()?, minor.parse().ok()?)));
+        match version {
+            Some((1, minor)) => {
+                self.version = Some((1, minor));
+                Ok(())
+            }
+            Some(_) => self.err_span("unsupported version", self.eaten, self.pos),
+            None => self.err_span("version", self.eaten, self.pos),
         }
     }
 
@@ -8,8 +8,14 @@ use core::cmp::Ordering;
 /// [`Parser::backward`] if mismatched.
 impl Parser<'_> {
     /// Match invisible boundaries and keep the gaps. (must matched once)
+    ///
+    /// The end of document and the comment leading by spaces are also
+    /// boundaries.
     pub fn bound(&mut self) -> PResult<()> {
-        self.sym_set(b":{}[] ,\n\r")?;
+        if self.food().is_empty() || self.food().starts_with(b"#") && self.after_ws() {
+            return Ok(());
+        }
+        self.sym_set(b":{}[] \t,\n\r")?;
         self.back(1);
         self.ws(TakeOpt::More(0))
     }
@@ -22,14 +28,30 @@ impl Parser<'_> {
 
     fn num_prefix(&mut self) -> PResult<()> {
         self.sym(b'-').unwrap_or_default();
-        self.take_while(u8::is_ascii_digit, TakeOpt::More(1))
+        self.digits(10)
+    }
+
+    /// Match digits with the radix, allows single underscore between the
+    /// digits as separator, e.g. `1_000`.
+    pub fn digits(&mut self, radix: u32) -> PResult<()> {
+        self.take_while(Self::ascii_digit(radix), TakeOpt::More(1))?;
+        while self
+            .context(|p| {
+                p.sym(b'_')?;
+                p.take_while(Self::ascii_digit(radix), TakeOpt::More(1))
+            })
+            .is_ok()
+        {}
+        Ok(())
     }
 
     /// Match integer.
     pub fn int(&mut self) -> PResult<String> {
         self.num_prefix()?;
         let mut s = self.text();
-        if s.as_bytes() == b"0" && self.context(|p| p.octal().is_ok() || p.hexadecimal().is_ok()) {
+        if s.as_bytes() == b"0"
+            && self.context(|p| p.octal().is_ok() || p.hexadecimal().is_ok() || p.binary().is_ok())
+        {
             s = self.text();
         }
         self.ws(TakeOpt::More(0))?;
@@ -39,29 +61,34 @@ impl Parser<'_> {
 
     fn octal(&mut self) -> PResult<()> {
         self.sym(b'o')?;
-        self.take_while(Self::ascii_digit(8), TakeOpt::More(1))
+        self.digits(8)
     }
 
     fn hexadecimal(&mut self) -> PResult<()> {
         self.sym(b'x')?;
-        self.take_while(Self::ascii_digit(16), TakeOpt::More(1))
+        self.digits(16)
     }
 
-    fn ascii_digit(i: u8) -> impl Fn(&u8) -> bool + 'static {
-        move |c| c.is_ascii_digit() || (*c > b'a' && *c < b'a' + i) || (*c > b'A' && *c < b'A' + i)
+    fn binary(&mut self) -> PResult<()> {
+        self.sym(b'b')?;
+        self.digits(2)
+    }
+
+    fn ascii_digit(i: u32) -> impl Fn(&u8) -> bool + 'static {
+        move |c| char::from(*c).is_digit(i)
     }
 
     /// Match float.
     pub fn float(&mut self) -> PResult<String> {
         self.num_prefix()?;
         self.sym(b'.')?;
-        self.take_while(u8::is_ascii_digit, TakeOpt::More(0))?;
+        self.context(|p| p.digits(10)).unwrap_or_default();
         let s = self.text();
         if self.count(|p| p.ws(TakeOpt::More(0)))? > 0 {
             self.back(1);
         }
         self.bound()?;
-        Ok(s.trim_end_matches(|c| ".0".contains(c)).to_string())
+        Ok(s.trim_end_matches('0').trim_end_matches('.').to_string())
     }
 
     /// Match float with scientific notation.
@@ -69,7 +96,7 @@ impl Parser<'_> {
         self.num_prefix()?;
         self.sym_set(b"eE")?;
         self.take_while(Self::is_in(b"+-"), TakeOpt::Range(0, 1))?;
-        self.take_while(u8::is_ascii_digit, TakeOpt::More(1))?;
+        self.digits(10)?;
         let s = self.text();
         if self.count(|p| p.ws(TakeOpt::More(0)))? > 0 {
             self.back(1);
@@ -78,35 +105,110 @@ impl Parser<'_> {
         Ok(s)
     }
 
+    /// Match timestamp, the date with optional time and time zone.
+    ///
+    /// For example, `2001-12-14`, `2001-12-14t21:59:43.10-05:00` and
+    /// `2001-12-14 21:59:43.10 Z`.
+    pub fn timestamp(&mut self) -> PResult<String> {
+        self.take_while(Self::ascii_digit(10), TakeOpt::Range(4, 4))?;
+        self.sym(b'-')?;
+        self.take_while(Self::ascii_digit(10), TakeOpt::Range(1, 2))?;
+        self.sym(b'-')?;
+        self.take_while(Self::ascii_digit(10), TakeOpt::Range(1, 2))?;
+        self.context(|p| -> PResult<()> {
+            if p.sym_set(b"Tt").is_err() {
+                p.take_while(Self::is_in(b" \t"), TakeOpt::More(1))?;
+            }
+            p.take_while(Self::ascii_digit(10), TakeOpt::Range(1, 2))?;
+            for _ in 0..2 {
+                p.sym(b':')?;
+                p.take_while(Self::ascii_digit(10), TakeOpt::Range(2, 2))?;
+            }
+            p.context(|p| {
+                p.sym(b'.')?;
+                p.take_while(Self::ascii_digit(10), TakeOpt::More(0))
+            })
+            .unwrap_or_default();
+            p.context(|p| -> PResult<()> {
+                p.ws(TakeOpt::More(0))?;
+                p.forward();
+                if p.sym(b'Z').is_ok() {
+                    return Ok(());
+                }
+                p.sym_set(b"+-")?;
+                p.take_while(Self::ascii_digit(10), TakeOpt::Range(1, 2))?;
+                p.context(|p| {
+                    p.sym(b':')?;
+                    p.take_while(Self::ascii_digit(10), TakeOpt::Range(2, 2))
+                })
+                .unwrap_or_default();
+                Ok(())
+            })
+            .unwrap_or_default();
+            Ok(())
+        })
+        .unwrap_or_default();
+        let s = self.text();
+        self.ws(TakeOpt::More(0))?;
+        self.bound()?;
+        Ok(s)
+    }
+
     /// Match quoted string.
+    ///
+    /// The escape sequences of double quoted string will be unescaped.
     pub fn string_quoted(&mut self, sym: u8, ignore: &[u8]) -> PResult<String> {
         self.context(|p| {
             p.sym(sym)?;
             p.forward();
             let mut v = String::new();
+            // The end of the last escaped character
+            let mut escaped = 0;
+            let mut patt = vec![b'\n', b'\r', sym];
+            if sym == b'"' {
+                patt.push(b'\\');
+            }
             p.ws(TakeOpt::More(0))?;
             v.push_str(&p.text());
             loop {
                 p.forward();
-                p.take_while(Self::not_in(&[b'\n', b'\r', b'\\', sym]), TakeOpt::More(0))?;
+                p.take_while(Self::not_in(&patt), TakeOpt::More(0))?;
                 v.push_str(&p.text());
                 p.forward();
+                if p.food().is_empty() {
+                    return p.err("quoted string");
+                }
                 if p.sym_seq(ignore).is_ok() {
                     v.push(char::from(sym));
-                } else if let Ok(mut t) = p.gap(false) {
-                    if v.ends_with('\\') {
-                        t -= 1;
+                } else if sym == b'"' && p.sym(b'\\').is_ok() {
+                    if let Ok(t) = p.gap(false) {
+                        // Line continuation
+                        for _ in 0..t - 1 {
+                            v.push('\n');
+                        }
+                        p.ws(TakeOpt::More(0))?;
+                    } else {
+                        match p.escape_seq() {
+                            Ok(c) => v.push(c),
+                            // Keep the unknown escape sequence
+                            Err(PError::Mismatch) => v.push('\\'),
+                            Err(e) => return Err(e),
+                        }
                     }
+                    escaped = v.len();
+                } else if let Ok(t) = p.gap(false) {
                     match t.cmp(&1) {
                         Ordering::Less => {}
                         Ordering::Equal => {
-                            v.truncate(v.trim_end().len());
                             // Manual wrapping
-                            if !v.ends_with("\\n") {
+                            let wrapped = escaped == v.len() && v.ends_with('\n');
+                            v.truncate(v.trim_end().len().max(escaped));
+                            if !wrapped {
                                 v.push(' ');
                             }
                         }
                         Ordering::Greater => {
+                            v.truncate(v.trim_end().len().max(escaped));
                             for _ in 0..t - 1 {
                                 v.push('\n');
                             }
@@ -114,8 +216,6 @@ impl Parser<'_> {
                     }
                     // Remove leading space
                     p.ws(TakeOpt::More(0))?;
-                } else if p.sym(b'\\').is_ok() {
-                    v.push('\\');
                 } else if p.sym(sym).is_ok() {
                     break;
                 }
@@ -125,52 +225,73 @@ impl Parser<'_> {
     }
 
     /// Match plain string.
+    ///
+    /// The string can be continued by the lines which are indented by the
+    /// level, and the line breaks are folded. A comment line or a document
+    /// marker terminates the string.
     pub fn string_plain(&mut self, level: usize, inner: bool) -> PResult<String> {
-        let mut patt = b"[]{}: \n\r".to_vec();
+        let mut patt = b"[]{}: \t\n\r".to_vec();
         if inner {
             patt.push(b',');
         }
+        // The comment is not a part of the scalar
+        if self.is_doc_marker() || self.food().starts_with(b"#") {
+            return Err(PError::Mismatch);
+        }
         self.context(|p| {
             let mut v = String::new();
             let mut is_leading = false;
             loop {
                 p.forward();
                 p.take_while(Self::not_in(&patt), TakeOpt::More(0))?;
-                v.push_str(&p.text());
+                let text = p.text();
+                if !text.is_empty() {
+                    is_leading = false;
+                }
+                v.push_str(&text);
                 p.forward();
                 if p.food().is_empty()
-                    || p.sym_seq(b": ").is_ok()
-                    || (p.sym(b':').is_ok() && p.nl().is_ok())
-                    || p.sym_seq(b" #").is_ok()
+                    || (p.sym(b':').is_ok()
+                        && matches!(p.food().first(), None | Some(b' ' | b'\t' | b'\n' | b'\r')))
+                    || (p.sym_set(b" \t").is_ok() && p.sym(b'#').is_ok())
                 {
                     p.backward();
                     break;
                 }
                 p.forward();
-                if p.sym_set(b": ").is_ok() {
-                    // Remove leading space
-                    if is_leading && p.text() == " " {
-                        v.truncate(v.trim_end().len());
+                if p.sym_set(b": \t").is_ok() {
+                    let text = p.text();
+                    // Skip the leading white spaces of the next line
+                    if is_leading && text != ":" {
+                        continue;
                     }
-                    v.push_str(&p.text());
+                    is_leading = false;
+                    v.push_str(&text);
                 } else if !inner && !v.is_empty() && p.sym_set(b"{}[]").is_ok() {
                     v.push_str(&p.text());
                     is_leading = false;
-                } else if p.ind(level).is_err() {
-                    if let Ok(t) = p.gap(true) {
+                } else if let Ok(t) = p.gap(false) {
+                    if p.ind(level).is_err() {
+                        break;
+                    }
+                    // Terminated by comment lines and document markers
+                    if p.food().trim_ascii_start().starts_with(b"#") || p.is_doc_marker() {
+                        p.backward();
+                        break;
+                    }
+                    // The scalar can start from the next line
+                    if !v.is_empty() {
+                        v.truncate(v.trim_end_matches([' ', '\t']).len());
                         if t == 1 {
                             v.push(' ');
                         }
                         for _ in 0..t - 1 {
                             v.push('\n');
                         }
-                        if p.ind(level).is_err() {
-                            break;
-                        }
-                    } else {
-                        break;
                     }
                     is_leading = true;
+                } else {
+                    break;
                 }
             }
             v.truncate(v.trim_end().len());
@@ -185,75 +306,245 @@ impl Parser<'_> {
     /// Match literal string.
     pub fn string_literal(&mut self, level: usize) -> PResult<String> {
         self.sym(b'|')?;
-        let chomp = self.chomp();
+        let (chomp, ind) = self.block_header();
         self.ws(TakeOpt::More(0))?;
-        let s = self.string_wrapped(level, b'\n', true)?;
+        let s = self.string_block(level, ind, b'\n', true)?;
         Ok(chomp(s))
     }
 
     /// Match folded string.
     pub fn string_folded(&mut self, level: usize) -> PResult<String> {
         self.sym(b'>')?;
-        let chomp = self.chomp();
+        let (chomp, ind) = self.block_header();
         self.ws(TakeOpt::More(0))?;
-        let s = self.string_wrapped(level, b' ', false)?;
+        let s = self.string_block(level, ind, b' ', false)?;
         Ok(chomp(s))
     }
 
+    /// Match the header of block scalar, the chomping option and the
+    /// indentation indicator can be in either order, e.g. `|2-` and `|-2`.
+    pub fn block_header(&mut self) -> (impl Fn(String) -> String, Option<usize>) {
+        let ind = self.block_ind();
+        let chomp = self.chomp();
+        (chomp, ind.or_else(|| self.block_ind()))
+    }
+
+    fn block_ind(&mut self) -> Option<usize> {
+        self.context(|p| {
+            p.take_while(|c| (b'1'..=b'9').contains(c), TakeOpt::One)
+                .ok()
+                .map(|_| p.text().parse().unwrap())
+        })
+    }
+
     /// Match string chomping option.
+    ///
+    /// + Strip (`-`): Remove all the trailing newlines.
+    /// + Keep (`+`): Keep all the trailing newlines.
+    /// + Clip (default): Keep only one trailing newline.
     pub fn chomp(&mut self) -> impl Fn(String) -> String {
         self.context(|p| {
             if p.sym(b'-').is_ok() {
-                |s: String| s.trim_end().to_string()
+                |s: String| s.trim_end_matches(['\n', '\r']).to_string()
             } else if p.sym(b'+').is_ok() {
                 |s| s
             } else {
-                |s: String| s.trim_end().to_string() + "\n"
+                |s: String| {
+                    let s = s.trim_end_matches(['\n', '\r']);
+                    if s.is_empty() {
+                        String::new()
+                    } else {
+                        s.to_string() + "\n"
+                    }
+                }
             }
         })
     }
 
     /// Match wrapped string.
+    ///
+    /// The indent of the block is detected from the first non-empty line,
+    /// and the block is ended by a less indented non-empty line.
+    /// The returned string keeps all the trailing newlines.
     pub fn string_wrapped(&mut self, level: usize, sep: u8, leading: bool) -> PResult<String> {
+        self.string_block(level, None, sep, leading)
+    }
+
+    /// Same as [`Parser::string_wrapped`], but the indent can be specified by
+    /// the indentation indicator, which is relative to the parent.
+    fn string_block(
+        &mut self,
+        level: usize,
+        ind: Option<usize>,
+        sep: u8,
+        leading: bool,
+    ) -> PResult<String> {
         self.context(|p| {
-            let mut v = String::new();
+            p.comment().unwrap_or_default();
+            let mut end = p.pos;
+            p.nl()?;
+            p.block_ind_define(level, ind);
+            let mut lines = Vec::new();
+            let mut trailing = 0;
             loop {
-                p.nl()?;
                 p.forward();
                 if p.ind(level).is_err() {
-                    if let Ok(t) = p.gap(false) {
-                        for _ in 0..t {
-                            v.push('\n');
-                        }
-                        if p.ind(level).is_err() {
-                            break;
-                        }
-                    } else {
-                        break;
+                    // Empty line or the end of block
+                    p.ws(TakeOpt::More(0))?;
+                    if p.nl().is_ok() {
+                        lines.push(String::new());
+                        trailing += 1;
+                        continue;
                     }
+                    break;
+                }
+                if p.indent[..=level].iter().sum::<usize>() == 0
+                    && (p.food().starts_with(b"---") || p.food().starts_with(b"..."))
+                {
+                    break;
                 }
                 p.forward();
                 p.take_while(Self::not_in(b"\n\r"), TakeOpt::More(0))?;
                 let s = p.text();
-                if leading {
-                    if !v.is_empty() {
-                        v.push(char::from(sep));
-                    }
-                    v.push_str(&s);
+                if s.is_empty() {
+                    trailing += 1;
                 } else {
-                    let s = s.trim_start();
-                    if !v.is_empty() && !v.ends_with(char::is_whitespace) {
-                        v.push(char::from(sep));
-                    }
-                    v.push_str(s);
+                    trailing = 0;
+                    end = p.pos;
+                }
+                lines.push(s);
+                if p.nl().is_err() {
+                    break;
                 }
             }
+            lines.truncate(lines.len() - trailing);
             // Keep the last wrap
-            p.back(1);
-            Ok(v + "\n")
+            p.pos = end;
+            let v = if leading {
+                lines.join(&char::from(sep).to_string())
+            } else {
+                // Folding: the lines are joined by the separator,
+                // but empty lines and more indented lines keep their newlines
+                let mut v = String::new();
+                let mut empty = 0;
+                let mut last_more = None;
+                for s in lines {
+                    if s.is_empty() {
+                        empty += 1;
+                        continue;
+                    }
+                    let more = s.starts_with([' ', '\t']);
+                    match last_more {
+                        None => v.push_str(&"\n".repeat(empty)),
+                        Some(false) if !more && empty == 0 => v.push(char::from(sep)),
+                        Some(false) if !more => v.push_str(&"\n".repeat(empty)),
+                        Some(_) => v.push_str(&"\n".repeat(empty + 1)),
+                    }
+                    v.push_str(&s);
+                    empty = 0;
+                    last_more = Some(more);
+                }
+                v
+            };
+            if v.is_empty() {
+                Ok("\n".repeat(trailing))
+            } else {
+                Ok(v + &"\n".repeat(trailing + 1))
+            }
         })
     }
 
+    /// Define the indent size of the wrapped string by its first non-empty
+    /// line. The indent must be greater than its parent, otherwise keep the
+    /// default size.
+    fn block_ind_define(&mut self, level: usize, ind: Option<usize>) {
+        if level >= self.indent.len() {
+            self.indent.resize(level + 1, 2);
+        }
+        if let Some(ind) = ind {
+            self.indent[level] = ind;
+            return;
+        }
+        let parent = self.indent[..level].iter().sum::<usize>();
+        let ind = self.context(|p| {
+            let pos = p.pos;
+            let ind = loop {
+                let ind = p.count(|p| p.take_while(Self::is_in(b" "), TakeOpt::More(0)))?;
+                if p.nl().is_err() {
+                    break ind;
+                }
+            };
+            p.pos = pos;
+            PResult::Ok(ind)
+        });
+        if let Ok(ind) = ind {
+            if ind > parent {
+                self.indent[level] = ind - parent;
+            }
+        }
+    }
+
+    /// Match an escape sequence behind the backslash, return the unescaped
+    /// character.
+    ///
+    /// The unknown escape sequence is mismatched if
+    /// [`Parser::strict_escapes`] is disabled.
+    pub fn escape_seq(&mut self) -> PResult<char> {
+        // Includes the backslash
+        let start = self.pos - 1;
+        let n = match self.food().first() {
+            Some(b'x') => 2,
+            Some(b'u') => 4,
+            Some(b'U') => 8,
+            Some(c) => {
+                let c = match c {
+                    b'0' => '\0',
+                    b'a' => '\x07',
+                    b'b' => '\x08',
+                    b't' | b'\t' => '\t',
+                    b'n' => '\n',
+                    b'v' => '\x0B',
+                    b'f' => '\x0C',
+                    b'r' => '\r',
+                    b'e' => '\x1B',
+                    b' ' => ' ',
+                    b'"' => '"',
+                    b'/' => '/',
+                    b'\\' => '\\',
+                    b'N' => '\u{85}',
+                    b'_' => '\u{A0}',
+                    b'L' => '\u{2028}',
+                    b'P' => '\u{2029}',
+                    _ if !self.strict_escapes => return Err(PError::Mismatch),
+                    _ => {
+                        let len = self.food_str().chars().next().map_or(1, char::len_utf8);
+                        return self.err_span("escape", start, self.pos + len);
+                    }
+                };
+                self.pos += 1;
+                self.forward();
+                return Ok(c);
+            }
+            None => return self.err_span("escape", start, self.pos),
+        };
+        self.pos += 1;
+        let len = self
+            .food()
+            .iter()
+            .take(n)
+            .take_while(|c| c.is_ascii_hexdigit())
+            .count();
+        if len < n {
+            return self.err_span("escape", start, self.pos + len);
+        }
+        self.forward();
+        self.pos += n;
+        match u32::from_str_radix(&self.text(), 16).map(char::from_u32) {
+            Ok(Some(c)) => Ok(c),
+            _ => self.err_span("escape", start, self.pos),
+        }
+    }
+
     /// Match an escaped string, return unescaped string.
     pub fn escape(doc: &str) -> String {
         let mut s = String::new();
@@ -295,34 +586,69 @@ impl Parser<'_> {
             let prefix = if !tag.is_empty() {
                 if p.sym(b'!').is_ok() {
                     // Tag prefix variable
-                    p.tag[&tag].clone()
+                    match p.tag.get(&tag) {
+                        Some(prefix) => prefix.clone(),
+                        None => return p.err_span("undefined tag handle", p.eaten - 1, p.pos),
+                    }
                 } else {
                     String::new()
                 }
             } else if p.sym(b'<').is_ok() {
-                // Full tag
-                let tag = p.context(|p| {
-                    p.take_while(Self::not_in(b" <>\n\r"), TakeOpt::More(1))?;
-                    Ok(p.text())
-                })?;
+                // Verbatim tag
+                let start = p.pos;
+                p.take_while(Self::not_in(b" <>\n\r"), TakeOpt::More(1))?;
+                let tag = p.percent_decode(start)?;
                 p.sym(b'>')?;
-                tag
+                return Ok(tag);
             } else if p.sym(b'!').is_ok() {
                 p.tag["!!"].clone()
             } else {
                 p.tag["!"].clone()
             };
-            let doc = p.context(|p| {
-                if p.identifier().is_ok() {
-                    p.text()
-                } else {
-                    String::new()
-                }
-            });
+            let start = p.pos;
+            let doc = if p.tag_suffix().is_ok() {
+                p.percent_decode(start)?
+            } else {
+                String::new()
+            };
             Ok(format!("{prefix}{doc}"))
         })
     }
 
+    /// Match the suffix of the tag, which is a URI without `!` and flow
+    /// indicators.
+    fn tag_suffix(&mut self) -> PResult<()> {
+        self.take_while(
+            |c| c.is_ascii_alphanumeric() || b"-#;/?:@&=+$_.~*'()%".contains(c),
+            TakeOpt::More(1),
+        )
+    }
+
+    /// Decode the percent-encoded URI from `start` to the current position,
+    /// such as `%20` for a space.
+    fn percent_decode(&self, start: usize) -> PResult<String> {
+        let mut v = Vec::new();
+        let mut i = start;
+        while i < self.pos {
+            let c = self.doc[i];
+            if c == b'%' {
+                let end = (i + 3).min(self.pos);
+                let hex = &self.doc[i + 1..end];
+                if hex.len() != 2 || !hex.iter().all(u8::is_ascii_hexdigit) {
+                    return self.err_span("escape", i, end);
+                }
+                // Hex digits are valid UTF-8
+                let hex = core::str::from_utf8(hex).unwrap();
+                v.push(u8::from_str_radix(hex, 16).unwrap());
+                i = end;
+            } else {
+                v.push(c);
+                i += 1;
+            }
+        }
+        String::from_utf8(v).or_else(|_| self.err_span("escape", start, self.pos))
+    }
+
     /// Match anchor definition.
     pub fn anchor(&mut self) -> PResult<String> {
         self.sym(b'&')?;
@@ -366,12 +692,25 @@ impl Parser<'_> {
         self.take_while(u8::is_ascii_whitespace, opt)
     }
 
+    /// Match any invisible characters and comments.
+    pub fn inv_comment(&mut self) -> PResult<()> {
+        loop {
+            self.inv(TakeOpt::More(0))?;
+            if self.context(Self::comment).is_err() {
+                break Ok(());
+            }
+        }
+    }
+
     /// Match and define new indent size.
     pub fn ind_define(&mut self, level: usize) -> PResult<()> {
         if level > 0 {
             self.ind(level - 1)?;
         }
-        let ind = self.count(|p| p.take_while(|c| c.is_ascii_whitespace(), TakeOpt::More(0)))?;
+        let ind = self.count(|p| p.take_while(Self::is_in(b" "), TakeOpt::More(0)))?;
+        if self.food().starts_with(b"\t") {
+            return self.err("tab indentation");
+        }
         if level == self.indent.len() {
             self.indent.push(ind);
         } else {
@@ -380,25 +719,51 @@ impl Parser<'_> {
         Ok(())
     }
 
+    /// Same as [`Parser::ind_define`], but the new indent must be greater
+    /// than its parent, except the root.
+    pub fn ind_define_more(&mut self, level: usize) -> PResult<()> {
+        let indent = self.indent.clone();
+        self.ind_define(level)?;
+        if level > 0 && self.indent[level] == 0 {
+            // The level is not defined, such as the sequence at the same
+            // indent was just mismatched
+            self.indent = indent;
+            self.indent.truncate(level);
+            self.backward();
+            Err(PError::Mismatch)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Match any optional invisible characters between two lines.
     ///
     /// Set `cmt` to `true` to ignore comments at the line end.
     pub fn gap(&mut self, cmt: bool) -> PResult<usize> {
         self.context(|p| {
-            if cmt {
-                p.comment().unwrap_or_default();
+            p.ws(TakeOpt::More(0))?;
+            let has_cmt = cmt && p.context(|p| p.comment().is_ok());
+            if p.nl().is_err() {
+                // The comment at the end of the document
+                if has_cmt && p.food().is_empty() {
+                    return Ok(1);
+                }
+                p.backward();
+                return Err(PError::Mismatch);
             }
-            p.nl()?;
             let mut t = 1;
             loop {
                 // Check point
                 p.forward();
                 p.ws(TakeOpt::More(0))?;
                 if cmt {
-                    p.comment().unwrap_or_default();
+                    p.context(|p| p.comment().unwrap_or_default());
                 }
                 if p.nl().is_err() {
-                    p.backward();
+                    // Keep the trailing spaces of the document
+                    if !p.food().is_empty() {
+                        p.backward();
+                    }
                     return Ok(t);
                 }
                 t += 1;
@@ -406,6 +771,19 @@ impl Parser<'_> {
         })
     }
 
+    /// Return true if the document marker `---` or `...` is placed at the
+    /// line start.
+    fn is_doc_marker(&self) -> bool {
+        let food = self.food();
+        (self.pos == 0 || matches!(self.doc[self.pos - 1], b'\n' | b'\r'))
+            && (food.starts_with(b"---") || food.starts_with(b"..."))
+            && matches!(food.get(3), None | Some(b' ' | b'\t' | b'\n' | b'\r'))
+    }
+
+    fn after_ws(&self) -> bool {
+        self.pos > 0 && matches!(self.doc[self.pos - 1], b' ' | b'\t')
+    }
+
     /// Match comment.
     pub fn comment(&mut self) -> PResult<()> {
         self.ws(TakeOpt::More(0))?;
@@ -20,11 +20,12 @@ pub enum TakeOpt {
 ///
 /// Its methods are actually the sub-parser of the syntax.
 pub struct Parser<'a> {
-    doc: &'a [u8],
+    pub(crate) doc: &'a [u8],
     indent: Vec<usize>,
     consumed: u64,
-    pub(crate) version_checked: bool,
+    pub(crate) version: Option<(u8, u8)>,
     pub(crate) tag: BTreeMap<String, String>,
+    pub(crate) strict_escapes: bool,
     /// Current position.
     pub pos: usize,
     /// Read position.
@@ -40,8 +41,9 @@ impl Default for Parser<'_> {
             doc: b"",
             indent: vec![0],
             consumed: 0,
-            version_checked: false,
+            version: None,
             tag,
+            strict_escapes: true,
             pos: 0,
             eaten: 0,
         }
@@ -55,21 +57,132 @@ impl<'a> Parser<'a> {
         Self::default().with_doc(doc)
     }
 
+    /// Create a parser with the bytes, return an error if the bytes are not
+    /// valid UTF-8.
+    ///
+    /// The bytes are borrowed without copying.
+    ///
+    /// ```
+    /// use yaml_peg::parser::Parser;
+    ///
+    /// let mut p = Parser::from_bytes("αβγ".as_bytes()).unwrap();
+    /// assert_eq!("αβγ", p.string_plain(0, false).unwrap());
+    /// assert!(Parser::from_bytes(b"a\xffb").is_err());
+    /// ```
+    pub fn from_bytes(doc: &'a [u8]) -> Result<Self, core::str::Utf8Error> {
+        core::str::from_utf8(doc)?;
+        Ok(Self::new(doc))
+    }
+
     /// Attach document on the parser.
     pub fn with_doc(self, doc: &'a [u8]) -> Self {
         Self { doc, ..self }
     }
 
+    /// Raise "escape" error on the unknown escape sequences of the double
+    /// quoted strings, such as `\q`. Enabled by default.
+    ///
+    /// If disabled, the unknown escape sequences are kept as they are.
+    ///
+    /// ```
+    /// use yaml_peg::parser::Parser;
+    ///
+    /// let doc = br#""a\qb""#;
+    /// assert!(Parser::new(doc).string_quoted(b'"', b"\\\"").is_err());
+    /// let mut p = Parser::new(doc).strict_escapes(false);
+    /// assert_eq!(r"a\qb", p.string_quoted(b'"', b"\\\"").unwrap());
+    /// ```
+    pub fn strict_escapes(self, strict_escapes: bool) -> Self {
+        Self { strict_escapes, ..self }
+    }
+
+    /// Reset the parser with a new document, the positions, indentations,
+    /// version and tag handles are cleared.
+    ///
+    /// ```
+    /// use yaml_peg::parser::Parser;
+    ///
+    /// let mut p = Parser::new(b"123");
+    /// assert_eq!("123", p.int().unwrap());
+    /// p.reset(b"456");
+    /// assert_eq!("456", p.int().unwrap());
+    /// ```
+    pub fn reset(&mut self, doc: &'a [u8]) {
+        self.doc = doc;
+        self.indent.clear();
+        self.indent.push(0);
+        self.tag.clear();
+        self.tag.insert("!".to_string(), String::new());
+        self.tag.insert("!!".to_string(), tag_prefix!().to_string());
+        self.consumed = 0;
+        self.version = None;
+        self.pos = 0;
+        self.eaten = 0;
+    }
+
     /// Show the right hand side string after the current cursor.
     pub fn food(&self) -> &'a [u8] {
         &self.doc[self.pos..]
     }
 
     /// Encoded version of the left characters.
-    pub fn food_str(&self) -> Cow<str> {
+    pub fn food_str(&self) -> Cow<'_, str> {
         String::from_utf8_lossy(&self.doc[self.pos..])
     }
 
+    /// The remaining input after the current position, it is the part which
+    /// is not matched yet.
+    ///
+    /// The eaten cursor is not considered, call [`Parser::backward`] to drop
+    /// the matched part which is not consumed by [`Parser::forward`].
+    /// If the document is not valid UTF-8, only the valid part is returned.
+    ///
+    /// ```
+    /// use yaml_peg::parser::Parser;
+    ///
+    /// let mut p = Parser::new(b"123, abc");
+    /// assert_eq!("123", p.int().unwrap());
+    /// assert_eq!(", abc", p.remainder());
+    /// p.forward();
+    /// p.sym(b',').unwrap();
+    /// assert_eq!(" abc", p.remainder());
+    /// p.backward();
+    /// assert_eq!(", abc", p.remainder());
+    /// ```
+    pub fn remainder(&self) -> &'a str {
+        let food = self.food();
+        match core::str::from_utf8(food) {
+            Ok(s) => s,
+            Err(e) => core::str::from_utf8(&food[..e.valid_up_to()]).unwrap(),
+        }
+    }
+
+    /// The character at the current position without moving the cursor,
+    /// `None` at the end of the document or an invalid UTF-8 sequence.
+    ///
+    /// ```
+    /// use yaml_peg::parser::Parser;
+    ///
+    /// let mut p = Parser::new("中a".as_bytes());
+    /// assert_eq!(Some('中'), p.peek());
+    /// assert_eq!(0, p.pos);
+    /// assert!(p.pos(3).peek_seq(b"a"));
+    /// ```
+    pub fn peek(&self) -> Option<char> {
+        let food = &self.food()[..self.food().len().min(4)];
+        let s = match core::str::from_utf8(food) {
+            Ok(s) => s,
+            Err(e) => core::str::from_utf8(&food[..e.valid_up_to()]).unwrap(),
+        };
+        s.chars().next()
+    }
+
+    /// Return true if the remaining input starts with the sequence, the
+    /// cursor is not moved.
+    pub fn peek_seq(&self, s: &[u8]) -> bool {
+        self.food().starts_with(s)
+    }
+
     /// Get the text from the eaten cursor to the current position.
     pub fn text(&mut self) -> String {
         if self.eaten < self.pos {
@@ -85,9 +198,44 @@ impl<'a> Parser<'a> {
 /// These sub-parser returns `Result<(), PError>`, and calling
 /// [`Parser::backward`] if mismatched.
 impl Parser<'_> {
-    /// Set the starting point if character boundary is valid.
+    /// Set the starting point if character boundary is valid, otherwise
+    /// the position is not changed.
+    ///
+    /// Use [`Parser::try_pos`] to check the position.
     pub fn pos(self, pos: usize) -> Self {
-        Self { pos, eaten: pos, ..self }
+        if self.is_boundary(pos) {
+            Self { pos, eaten: pos, ..self }
+        } else {
+            self
+        }
+    }
+
+    /// Set the starting point, return `Err` with the position if it is out
+    /// of range or not at a character boundary.
+    ///
+    /// ```
+    /// use yaml_peg::parser::Parser;
+    ///
+    /// let doc = "a: 中文".as_bytes();
+    /// assert_eq!(3, Parser::new(doc).try_pos(3).unwrap().pos);
+    /// assert_eq!(9, Parser::new(doc).try_pos(9).unwrap().pos);
+    /// assert_eq!(Err(4), Parser::new(doc).try_pos(4).map(|p| p.pos));
+    /// assert_eq!(Err(10), Parser::new(doc).try_pos(10).map(|p| p.pos));
+    /// ```
+    pub fn try_pos(self, pos: usize) -> Result<Self, usize> {
+        if self.is_boundary(pos) {
+            Ok(self.pos(pos))
+        } else {
+            Err(pos)
+        }
+    }
+
+    fn is_boundary(&self, pos: usize) -> bool {
+        match self.doc.get(pos) {
+            // Not an UTF-8 continuation byte
+            Some(c) => c & 0xC0 != 0x80,
+            None => pos == self.doc.len(),
+        }
     }
 
     /// Get the indicator.
@@ -95,11 +243,63 @@ impl Parser<'_> {
         self.consumed + self.pos as u64
     }
 
+    /// The line number of the current position, starts from 1.
+    ///
+    /// ```
+    /// use yaml_peg::parser::{Parser, TakeOpt};
+    ///
+    /// let mut p = Parser::new(b"a: 1\nb: 2");
+    /// assert_eq!((1, 1), (p.line(), p.column()));
+    /// p.take_while(Parser::not_in(b"\n"), TakeOpt::More(0)).unwrap();
+    /// assert_eq!((1, 5), (p.line(), p.column()));
+    /// p.nl().unwrap();
+    /// assert_eq!((2, 1), (p.line(), p.column()));
+    /// ```
+    pub fn line(&self) -> usize {
+        pos_to_line_col(self.doc, self.indicator()).0
+    }
+
+    /// The column number of the current position, starts from 1.
+    ///
+    /// The column is counted by the characters, same as [`pos_to_line_col`].
+    pub fn column(&self) -> usize {
+        pos_to_line_col(self.doc, self.indicator()).1
+    }
+
+    /// The version `(major, minor)` declared by the `%YAML` directive.
+    ///
+    /// ```
+    /// use yaml_peg::{parser::Loader, repr::RcRepr};
+    ///
+    /// let mut loader = Loader::<RcRepr>::new(b"%YAML 1.2\n--- a");
+    /// loader.parse().unwrap();
+    /// assert_eq!(Some((1, 2)), loader.version());
+    /// ```
+    pub fn version(&self) -> Option<(u8, u8)> {
+        self.version
+    }
+
     /// A short function to raise error.
+    ///
+    /// The error span is the token at the current position, which is ended
+    /// by white spaces or flow indicators.
     pub fn err<R>(&self, name: &'static str) -> PResult<R> {
+        let end = self.pos
+            + self
+                .food()
+                .iter()
+                .position(|c| b" \t\n\r,[]{}".contains(c))
+                .unwrap_or(self.food().len());
+        self.err_span(name, self.pos, end)
+    }
+
+    /// Raise error with the span `start..end` of the document.
+    pub fn err_span<R>(&self, name: &'static str, start: usize, end: usize) -> PResult<R> {
         Err(PError::Terminate {
             name,
-            msg: indicated_msg(self.doc, self.indicator()),
+            pos: self.consumed + start as u64,
+            end: self.consumed + end as u64,
+            msg: indicated_msg(self.doc, self.consumed + start as u64),
         })
     }
 
@@ -226,6 +426,33 @@ impl Parser<'_> {
         }
     }
 
+    /// The number of spaces required by [`Parser::ind`] at the nested
+    /// level, the undefined levels are counted as two spaces.
+    ///
+    /// Custom sub-parsers can use it to align with the block collections.
+    ///
+    /// ```
+    /// use yaml_peg::parser::Parser;
+    ///
+    /// let mut p = Parser::new(b"    a");
+    /// assert_eq!(0, p.indent(0));
+    /// assert_eq!(4, p.indent(2));
+    /// p.ind_define(1).unwrap();
+    /// assert_eq!(4, p.indent(1));
+    /// assert_eq!(6, p.indent(2));
+    /// ```
+    pub fn indent(&self, level: usize) -> usize {
+        let n = self.indent.len().min(level + 1);
+        self.indent[..n].iter().sum::<usize>() + 2 * (level + 1 - n)
+    }
+
+    /// Define the indent of the compact collection behind an indicator,
+    /// such as `-   a: b`.
+    pub fn ind_compact(&mut self, level: usize, width: usize) {
+        self.indent.resize(level, 2);
+        self.indent.push(width);
+    }
+
     /// Match indent.
     pub fn ind(&mut self, level: usize) -> PResult<()> {
         if level >= self.indent.len() {
@@ -237,6 +464,9 @@ impl Parser<'_> {
             self.indent.drain(level + 1..);
         }
         for _ in 0..self.indent[..=level].iter().sum() {
+            if self.food().starts_with(b"\t") {
+                return self.err("tab indentation");
+            }
             self.sym(b' ')?;
         }
         Ok(())
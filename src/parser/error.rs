@@ -1,4 +1,8 @@
-use alloc::string::String;
+use crate::indicated_msg;
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use core::fmt::{Display, Error, Formatter};
 
 /// Type of the parser result.
@@ -15,12 +19,142 @@ pub enum PError {
     Terminate {
         /// Name of sub-parser group.
         name: &'static str,
+        /// Start position of the error.
+        pos: u64,
+        /// End position of the error, the span `pos..end` is the invalid token.
+        end: u64,
         /// Document position.
         msg: String,
     },
 }
 
+macro_rules! error_kind {
+    ($($kind:ident = $name:literal,)+) => {
+        /// The kind of the parser error, which is matched from the name of
+        /// [`PError::Terminate`].
+        ///
+        /// Please see [module level document](super) for the meaning of each kind.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ErrorKind {
+            $(#[doc = $name] $kind,)+
+        }
+
+        impl ErrorKind {
+            /// All the kinds.
+            #[cfg(test)]
+            pub(crate) const ALL: &'static [Self] = &[$(Self::$kind),+];
+
+            /// Get the kind from the name of the sub-parser group.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $($name => Some(Self::$kind),)+
+                    _ => None,
+                }
+            }
+
+            /// The name of the sub-parser group.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$kind => $name,)+
+                }
+            }
+        }
+    };
+}
+
+error_kind! {
+    DocumentSplitter = "document splitter",
+    CheckedVersion = "checked version",
+    Version = "version",
+    UnsupportedVersion = "unsupported version",
+    UndefinedTagHandle = "undefined tag handle",
+    TabIndentation = "tab indentation",
+    Indentation = "indentation",
+    MaxDepth = "max depth",
+    FlowSequenceItem = "flow sequence item",
+    FlowSequenceTerminator = "flow sequence terminator",
+    FlowMapKey = "flow map key",
+    FlowMapValue = "flow map value",
+    FlowMapSplitter = "flow map splitter",
+    FlowMapTerminator = "flow map terminator",
+    SequenceItem = "sequence item",
+    SequenceTerminator = "sequence terminator",
+    MapKey = "map key",
+    MapValue = "map value",
+    MapSplitter = "map splitter",
+    MapTerminator = "map terminator",
+    DuplicatedKey = "duplicated key",
+    QuotedString = "quoted string",
+    Escape = "escape",
+    Binary = "binary",
+    DuplicatedAnchor = "duplicated anchor definition",
+    UndefinedAnchor = "anchor referenced before definition",
+}
+
 impl PError {
+    /// The kind of the error, `None` if mismatched or the name is
+    /// customized.
+    ///
+    /// ```
+    /// use yaml_peg::{parse, parser::ErrorKind, repr::RcRepr};
+    ///
+    /// let e = parse::<RcRepr>("a: *b").unwrap_err();
+    /// assert_eq!(e.kind(), Some(ErrorKind::UndefinedAnchor));
+    /// ```
+    pub fn kind(&self) -> Option<ErrorKind> {
+        match self {
+            Self::Mismatch => None,
+            Self::Terminate { name, .. } => ErrorKind::from_name(name),
+        }
+    }
+
+    /// The byte span of the invalid token, `None` if mismatched.
+    ///
+    /// ```
+    /// use yaml_peg::{parse, repr::RcRepr};
+    ///
+    /// let e = parse::<RcRepr>("a: \"\\xZZ\"").unwrap_err();
+    /// assert_eq!(e.span(), Some((4, 6)));
+    /// let e = parse::<RcRepr>("a: \"\\uD800\"").unwrap_err();
+    /// assert_eq!(e.span(), Some((4, 10)));
+    /// ```
+    pub fn span(&self) -> Option<(u64, u64)> {
+        match self {
+            Self::Mismatch => None,
+            Self::Terminate { pos, end, .. } => Some((*pos, *end)),
+        }
+    }
+
+    /// Render the error with the document, the offending line and a `^`
+    /// marker at the column are shown after the message.
+    ///
+    /// This is useful when the document is different from the parsed one,
+    /// for example, the parser only received a part of the file.
+    ///
+    /// ```
+    /// use yaml_peg::{parse, repr::RcRepr};
+    ///
+    /// let doc = "a:\t[b,\tc";
+    /// let e = parse::<RcRepr>(doc).unwrap_err();
+    /// assert_eq!(
+    ///     e.render(doc),
+    ///     "invalid flow sequence terminator: \n\n1:9\na:\t[b,\tc\n  \t   \t ^"
+    /// );
+    /// ```
+    pub fn render(&self, doc: &str) -> String {
+        match self {
+            Self::Mismatch => "not matched".to_string(),
+            Self::Terminate { name, pos, .. } => {
+                let pos = (*pos).min(doc.len() as u64);
+                format!(
+                    "invalid {}: \n\n{}",
+                    name,
+                    indicated_msg(doc.as_bytes(), pos)
+                )
+            }
+        }
+    }
+
     /// A "than" function for the error handling, execute the given function
     /// when mismatched.
     pub fn or<R, F>(self, f: F) -> Result<R, Self>
@@ -38,7 +172,7 @@ impl Display for PError {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         match self {
             Self::Mismatch => write!(f, "not matched"),
-            Self::Terminate { name, msg } => {
+            Self::Terminate { name, msg, .. } => {
                 write!(f, "invalid {}: \n\n{}", name, msg)
             }
         }
@@ -47,3 +181,10 @@ impl Display for PError {
 
 #[cfg(feature = "std")]
 impl std::error::Error for PError {}
+
+#[cfg(feature = "std")]
+impl From<PError> for std::io::Error {
+    fn from(e: PError) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
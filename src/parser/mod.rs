@@ -10,46 +10,79 @@
 //!
 //! # Errors
 //!
+//! The error names are listed below, they can be matched by [`ErrorKind`]
+//! through [`PError::kind`].
+//!
 //! ## Document
 //!
 //! **WRONG**: Invalid tag directive will be ignored.
 //!
-//! + document splitter: Error about the document splitter `---` / `...`.
+//! + document splitter: Error about the document splitter `---` / `...`,
+//!   or there are multiple documents when parsing single document.
 //! + checked version: Version directive `%YAML 1.2` is used again.
-//! + version: Version directive is wrong, must be `1.2`.
+//! + version: Version directive is wrong, must be `<major>.<minor>`.
+//! + unsupported version: The major version of directive is not `1`.
+//! + undefined tag handle: The tag handle like `!e!` is not defined by the
+//!   `%TAG` directive.
 //!
 //! ## Structure
 //!
+//! + tab indentation: Tabs cannot be used for indentation.
+//! + indentation: The item is indented more than the other items of the
+//!   block collection, the message shows the expected and found columns.
+//! + max depth: The collections are nested deeper than
+//!   [`Loader::max_depth`].
+//!
 //! ### Flow Array
 //!
 //! + flow sequence item: Item in `[]` bracket is invalid.
+//! + flow sequence terminator: The `[]` bracket is not closed, or the items
+//!   are not separated by `,`.
 //!
 //! ### Flow Map
 //!
 //! + flow map key: Key of map item in `{}` bracket is invalid.
 //! + flow map value: Value of map item in `{}` bracket is invalid.
 //! + flow map splitter: Splitter `:` of map item in `{}` bracket is invalid.
+//! + flow map terminator: The `{}` bracket is not closed, or the items are
+//!   not separated by `,`.
 //!
 //! ### Array
 //!
 //! + sequence item: Item behind `-` indicator is invalid.
 //! + sequence terminator: The end of sequence is invalid, may caused by the
-//! last item   (like wrapped string).
+//!   last item (like wrapped string).
 //!
 //! ### Map
 //!
-//! + map key: Key of map item is invalid.
+//! + map key: Key of map item is invalid, or the implicit key spans multiple lines.
 //! + map value: Value of map item is invalid.
 //! + map splitter: Splitter `:` of map item is invalid.
+//! + duplicated key: The key appears twice in the same map, only raised in
+//!   the strict keys mode, see [`Loader::strict_keys`]. This also applies to
+//!   the flow map.
 //! + map terminator: The end of map is invalid, may caused by the last value
 //!   (like wrapped string).
+//!
+//! ## Scalar
+//!
+//! + quoted string: The quoted string is not terminated.
+//! + escape: Invalid escape sequence in double quoted string, or invalid
+//!   percent-encoded character in tag. The unknown escape sequences can be
+//!   allowed by [`Loader::strict_escapes`].
+//! + binary: The string with `!!binary` tag is not a valid base64 string.
+//! + duplicated anchor definition: The anchor is defined twice in a document.
+//! + anchor referenced before definition: The alias is used before its anchor
+//!   is defined, which is allowed in cyclic mode.
 pub use self::{
     base::{Parser, TakeOpt},
-    error::{PError, PResult},
+    error::{ErrorKind, PError, PResult},
+    visitor::Visitor,
 };
 use crate::{repr::Repr, *};
 use alloc::{
     collections::BTreeMap,
+    format,
     string::{String, ToString},
     vec,
     vec::Vec,
@@ -58,6 +91,7 @@ use core::ops::{Deref, DerefMut};
 
 mod base;
 mod error;
+mod visitor;
 
 macro_rules! tag_prefix {
     () => {
@@ -92,8 +126,10 @@ pub const DEFAULT_PREFIX: &str = tag_prefix!();
 /// + They will move the current cursor if matched.
 /// + Returned value:
 ///     + `Result<(), PError>` represents the sub-parser can be matched and
-/// mismatched.     + [`PError`] represents the sub-parser can be totally
-/// breaked when mismatched. + Use `?` to match a condition.
+///       mismatched.
+///     + [`PError`] represents the sub-parser can be totally breaked when
+///       mismatched.
+/// + Use `?` to match a condition.
 /// + Use [`Result::unwrap_or_default`] to match an optional condition.
 /// + Method [`Parser::forward`] is used to move on.
 /// + Method [`Parser::text`] is used to get the matched string.
@@ -102,8 +138,19 @@ pub struct Loader<'a, R: Repr> {
     /// Parser base.
     pub parser: Parser<'a>,
     cyclic_mode: bool,
+    strict_keys: bool,
+    bool_1_1: bool,
+    undotted_float: bool,
+    max_depth: usize,
+    json_stream: bool,
+    style: Style,
     anchors: Vec<Anchors<R>>,
     doc_ind: usize,
+    explicit_end: bool,
+    flow_end: bool,
+    recover: bool,
+    recovered: Option<usize>,
+    errors: Vec<PError>,
 }
 
 impl<'a, R: Repr> Loader<'a, R> {
@@ -112,9 +159,46 @@ impl<'a, R: Repr> Loader<'a, R> {
         Self {
             parser: Parser::new(doc),
             cyclic_mode: false,
+            strict_keys: false,
+            bool_1_1: false,
+            undotted_float: false,
+            max_depth: 128,
+            json_stream: false,
+            style: Style::Plain,
             anchors: Vec::new(),
             doc_ind: 0,
+            explicit_end: false,
+            flow_end: false,
+            recover: false,
+            recovered: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Create YAML loader with the bytes, return an error if the bytes are not
+    /// valid UTF-8. See [`Parser::from_bytes`].
+    pub fn from_bytes(doc: &'a [u8]) -> Result<Self, core::str::Utf8Error> {
+        core::str::from_utf8(doc)?;
+        Ok(Self::new(doc))
+    }
+}
+
+/// The lazy iterator of the documents, created by [`Loader::documents`].
+pub struct Documents<'l, 'a, R: Repr> {
+    loader: &'l mut Loader<'a, R>,
+    done: bool,
+}
+
+impl<R: Repr> Iterator for Documents<'_, '_, R> {
+    type Item = PResult<Node<R>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
         }
+        let ret = self.loader.next_doc().transpose();
+        self.done = !matches!(ret, Some(Ok(_)));
+        ret
     }
 }
 
@@ -134,7 +218,7 @@ impl<'a, R: Repr> Loader<'a, R> {
 ///
 /// The `flow` parameter presents that the expression is in a **flow**
 /// expression.
-impl<R: Repr> Loader<'_, R> {
+impl<'a, R: Repr> Loader<'a, R> {
     /// Keep the anchor insertion.
     ///
     /// + Allow alias used before undefined anchor created. E.g., cyclic data.
@@ -146,6 +230,143 @@ impl<R: Repr> Loader<'_, R> {
         Self { cyclic_mode, ..self }
     }
 
+    /// Raise error when a key appears twice in the same map, the error
+    /// message includes the position of the first one.
+    ///
+    /// By default, the duplicated keys are allowed, the last one wins and it
+    /// is moved to the end of the map.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parser::{ErrorKind, Loader}, repr::RcRepr};
+    ///
+    /// let doc = b"a: 1\nb: 2\na: 3";
+    /// let n = Loader::<RcRepr>::new(doc).parse().unwrap();
+    /// assert_eq!(n[0], node!({"b" => 2, "a" => 3}));
+    /// let e = Loader::<RcRepr>::new(doc).strict_keys(true).parse().unwrap_err();
+    /// assert_eq!(e.kind(), Some(ErrorKind::DuplicatedKey));
+    /// assert_eq!(e.span(), Some((10, 11)));
+    /// ```
+    pub fn strict_keys(self, strict_keys: bool) -> Self {
+        Self { strict_keys, ..self }
+    }
+
+    /// Recognize the booleans of YAML 1.1 in plain scalars, which are `yes`,
+    /// `no`, `on`, `off`, `y` and `n` in lowercase, capitalized or uppercase.
+    ///
+    /// By default, only the YAML 1.2 booleans `true` and `false` are
+    /// recognized, and the others are strings.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parser::Loader, repr::RcRepr};
+    ///
+    /// let doc = b"[on, No, 'yes']";
+    /// let n = Loader::<RcRepr>::new(doc).parse().unwrap();
+    /// assert_eq!(n[0], node!(["on", "No", "yes"]));
+    /// let n = Loader::<RcRepr>::new(doc).bool_1_1(true).parse().unwrap();
+    /// assert_eq!(n[0], node!([true, false, "yes"]));
+    /// ```
+    pub fn bool_1_1(self, bool_1_1: bool) -> Self {
+        Self { bool_1_1, ..self }
+    }
+
+    /// Recognize the special floats without the dot in plain scalars, which
+    /// are `inf`, `+inf`, `-inf` and `nan` in the same cases as the dotted
+    /// ones.
+    ///
+    /// By default, only the YAML 1.2 spellings such as `.inf` and `.nan` are
+    /// recognized, and the others are strings.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parser::Loader, repr::RcRepr};
+    ///
+    /// let doc = b"[inf, -Inf, 'nan']";
+    /// let n = Loader::<RcRepr>::new(doc).parse().unwrap();
+    /// assert_eq!(n[0], node!(["inf", "-Inf", "nan"]));
+    /// let n = Loader::<RcRepr>::new(doc).undotted_float(true).parse().unwrap();
+    /// assert_eq!(n[0], node!([f64::INFINITY, f64::NEG_INFINITY, "nan"]));
+    /// ```
+    pub fn undotted_float(self, undotted_float: bool) -> Self {
+        Self { undotted_float, ..self }
+    }
+
+    /// The maximum nesting depth of the collections, raise "max depth" error
+    /// if exceeded. The default is 128.
+    ///
+    /// The parser is recursive, this limit prevents the stack overflow of
+    /// the untrusted documents. The default depth fits in the 2 MiB stack of
+    /// the spawned threads even in the debug build, increase it only if the
+    /// thread has enough stack.
+    ///
+    /// ```
+    /// use yaml_peg::{parser::{ErrorKind, Loader}, repr::RcRepr};
+    ///
+    /// let doc = b"[[[1]]]";
+    /// assert!(Loader::<RcRepr>::new(doc).max_depth(3).parse().is_ok());
+    /// let e = Loader::<RcRepr>::new(doc).max_depth(2).parse().unwrap_err();
+    /// assert_eq!(e.kind(), Some(ErrorKind::MaxDepth));
+    /// ```
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Split the concatenated JSON documents, such as `{"a": 1}{"b": 2}`,
+    /// without the document splitter `---`.
+    ///
+    /// If a document starts with `[` or `{` and ends with `]` or `}`, the
+    /// next document can start right after it with `[` or `{`. The other
+    /// documents still need the splitter.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parser::Loader, repr::RcRepr};
+    ///
+    /// let doc = br#"{"a": 1}{"b": [2]}"#;
+    /// assert!(Loader::<RcRepr>::new(doc).parse().is_err());
+    /// let n = Loader::<RcRepr>::new(doc).json_stream(true).parse().unwrap();
+    /// assert_eq!(n, [node!({"a" => 1}), node!({"b" => node!([2])})]);
+    /// ```
+    pub fn json_stream(self, json_stream: bool) -> Self {
+        Self { json_stream, ..self }
+    }
+
+    /// Raise "escape" error on the unknown escape sequences, or keep them
+    /// as they are. See [`Parser::strict_escapes`].
+    ///
+    /// ```
+    /// use yaml_peg::{node, parser::Loader, repr::RcRepr};
+    ///
+    /// let doc = br#"a: "C:\dir""#;
+    /// assert!(Loader::<RcRepr>::new(doc).parse().is_err());
+    /// let n = Loader::<RcRepr>::new(doc).strict_escapes(false).parse().unwrap();
+    /// assert_eq!(n[0], node!({"a" => r"C:\dir"}));
+    /// ```
+    pub fn strict_escapes(self, strict_escapes: bool) -> Self {
+        let parser = self.parser.strict_escapes(strict_escapes);
+        Self { parser, ..self }
+    }
+
+    /// Reset the loader with a new document, the recorded anchors are
+    /// cleared, and the options are kept.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parser::Loader, repr::RcRepr};
+    ///
+    /// let mut loader = Loader::<RcRepr>::new(b"&a [1, *a]").cyclic_mode(true);
+    /// assert_eq!(vec![node!([1, node!(*"a")])], loader.parse().unwrap());
+    /// loader.reset(b"{b: 2}");
+    /// assert_eq!(vec![node!({"b" => 2})], loader.parse().unwrap());
+    /// assert!(loader.get_anchors()[0].is_empty());
+    /// ```
+    pub fn reset(&mut self, doc: &'a [u8]) {
+        self.parser.reset(doc);
+        self.style = Style::Plain;
+        self.anchors.clear();
+        self.doc_ind = 0;
+        self.explicit_end = false;
+        self.flow_end = false;
+        self.recovered = None;
+        self.errors.clear();
+    }
+
     /// Consume this loader and return the recorded anchors.
     pub fn get_anchors(self) -> Vec<Anchors<R>> {
         self.anchors
@@ -153,27 +374,155 @@ impl<R: Repr> Loader<'_, R> {
 
     /// YAML entry point, return entire doc if exist.
     pub fn parse(&mut self) -> PResult<Vec<Node<R>>> {
+        self.documents().collect()
+    }
+
+    /// Parse exactly one document, raise "document splitter" error if there
+    /// are more documents.
+    ///
+    /// The empty document is a null node.
+    pub fn parse_single(&mut self) -> PResult<Node<R>> {
+        let node = self.next_doc()?;
+        self.gap(true).unwrap_or_default();
+        match node {
+            Some(node) if self.food().is_empty() => Ok(node),
+            _ => self.err("document splitter"),
+        }
+    }
+
+    /// Parse the documents and try to recover from the errors. Return the
+    /// documents and all the errors.
+    ///
+    /// The invalid part is skipped to the end of the line, or to the next
+    /// `,`, `]` and `}` in the flow collections, and the failed value is
+    /// replaced by a null. If the document cannot be recovered, it becomes a
+    /// null and the parser continues from the next `---`.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parser::Loader, repr::RcRepr};
+    ///
+    /// let mut loader = Loader::<RcRepr>::new(b"a: 1\nb: \"\\q\"\nc: 2\nd: 'e");
+    /// let (root, errors) = loader.parse_recover();
+    /// assert_eq!(root, [node!({"a" => 1, "b" => (), "c" => 2, "d" => ()})]);
+    /// assert_eq!(errors.len(), 2);
+    /// ```
+    pub fn parse_recover(&mut self) -> (Vec<Node<R>>, Vec<PError>) {
+        self.recover = true;
+        let mut v = Vec::new();
         loop {
-            match self.context(Parser::directive) {
-                Ok(()) => (),
-                Err(PError::Mismatch) => break,
-                Err(e) => return Err(e),
+            let begin = self.pos;
+            let e = match self.next_doc() {
+                Ok(Some(node)) => {
+                    v.push(node);
+                    continue;
+                }
+                Ok(None) => break,
+                Err(e) => e,
+            };
+            let pos = match e {
+                PError::Terminate { pos, .. } => pos,
+                PError::Mismatch => self.indicator(),
+            };
+            self.errors.push(e);
+            if self.anchors.len() > self.doc_ind {
+                // The document is failed
+                v.push(Node::new(Yaml::Null, pos, ""));
+                self.doc_ind += 1;
+            }
+            // Skip to the next document
+            let offset = self.indicator() - self.pos as u64;
+            let start = ((pos - offset) as usize).clamp(begin + 1, self.doc.len());
+            let next = (start..self.doc.len()).find(|&i| {
+                matches!(self.doc[i - 1], b'\n' | b'\r') && self.doc[i..].starts_with(b"---")
+            });
+            match next {
+                Some(i) => self.pos = i,
+                None => break,
             }
+            self.forward();
+            self.explicit_end = false;
+            self.flow_end = false;
         }
-        self.gap(true).unwrap_or_default();
-        self.sym_seq(b"---").unwrap_or_default();
-        let mut v = vec![self.doc()?];
-        loop {
+        self.recover = false;
+        (v, core::mem::take(&mut self.errors))
+    }
+
+    /// Record the error and skip the invalid part under the recovery mode,
+    /// return a null node as the placeholder. Otherwise, return the error.
+    ///
+    /// The invalid part is ended by the line break, or the flow indicators
+    /// if `flow` is true.
+    fn recover(&mut self, e: PError, flow: bool) -> PResult<Node<R>> {
+        let pos = match e {
+            PError::Terminate { pos, .. } if self.recover => pos,
+            _ => return Err(e),
+        };
+        let offset = self.indicator() - self.pos as u64;
+        let start = ((pos - offset) as usize).min(self.doc.len());
+        let end = self.doc[start..]
+            .iter()
+            .position(|c| matches!(c, b'\n' | b'\r') || flow && matches!(c, b',' | b']' | b'}'))
+            .map_or(self.doc.len(), |i| start + i);
+        // Stop if the parser cannot go forward
+        if self.recovered.is_some_and(|r| end <= r) {
+            return Err(e);
+        }
+        self.recovered = Some(end);
+        self.errors.push(e);
+        self.pos = end;
+        self.forward();
+        Ok(Node::new(Yaml::Null, pos, ""))
+    }
+
+    /// Parse the documents lazily, the next document will be parsed when
+    /// the iterator is consumed. The iterator stops after the first error,
+    /// and a new iterator will continue from the last parsed document.
+    ///
+    /// ```
+    /// use yaml_peg::{node, parser::Loader, repr::RcRepr};
+    ///
+    /// let mut loader = Loader::<RcRepr>::new(b"--- a\n--- b\n--- [c");
+    /// let docs = loader.documents().take(2).collect::<Result<Vec<_>, _>>();
+    /// assert_eq!(docs.unwrap(), [node!("a"), node!("b")]);
+    /// ```
+    pub fn documents(&mut self) -> Documents<'_, 'a, R> {
+        Documents { loader: self, done: false }
+    }
+
+    fn next_doc(&mut self) -> PResult<Option<Node<R>>> {
+        if self.doc_ind == 0 || self.explicit_end {
+            if self.doc_ind == 0 {
+                // Byte order mark
+                self.sym_seq("\u{FEFF}".as_bytes()).unwrap_or_default();
+                self.forward();
+            } else {
+                // The document end marker allows the directives of the next
+                // document, and the "---" can be omitted
+                self.gap(true).unwrap_or_default();
+                if self.food().is_empty() {
+                    return Ok(None);
+                }
+                self.version = None;
+            }
+            loop {
+                match self.context(Parser::directive) {
+                    Ok(()) => (),
+                    Err(PError::Mismatch) => break,
+                    Err(e) => return Err(e),
+                }
+            }
+            self.gap(true).unwrap_or_default();
+            self.sym_seq(b"---").unwrap_or_default();
+        } else {
             self.gap(true).unwrap_or_default();
             if self.food().is_empty() {
-                break;
+                return Ok(None);
             }
-            if self.sym_seq(b"---").is_err() {
+            if self.sym_seq(b"---").is_err() && !(self.flow_end && self.is_flow_start()) {
                 return self.err("document splitter");
             }
-            v.push(self.doc()?);
         }
-        Ok(v)
+        self.doc().map(Some)
     }
 
     /// Match one doc block.
@@ -181,14 +530,28 @@ impl<R: Repr> Loader<'_, R> {
         self.context(|p| p.bound().unwrap_or_default());
         self.forward();
         self.anchors.push(Anchors::new());
+        let flow = self.json_stream && self.is_flow_start();
         let ret = self.scalar(0, false, false)?;
+        self.flow_end = flow
+            && matches!(
+                self.doc[..self.pos]
+                    .iter()
+                    .rev()
+                    .find(|c| !c.is_ascii_whitespace()),
+                Some(b']' | b'}')
+            );
         self.gap(true).unwrap_or_default();
-        self.sym_seq(b"...").unwrap_or_default();
+        self.explicit_end = self.sym_seq(b"...").is_ok();
         self.forward();
         self.doc_ind += 1;
         Ok(ret)
     }
 
+    fn is_flow_start(&self) -> bool {
+        let mut food = self.food().iter();
+        matches!(food.find(|c| !c.is_ascii_whitespace()), Some(b'[' | b'{'))
+    }
+
     /// Match doc end.
     pub fn doc_end(&mut self) -> bool {
         if self.food().is_empty() {
@@ -206,10 +569,15 @@ impl<R: Repr> Loader<'_, R> {
 
     /// Match scalar.
     pub fn scalar(&mut self, level: usize, map: bool, flow: bool) -> PResult<Node<R>> {
+        if level > self.max_depth {
+            return self.err("max depth");
+        }
         self.scalar_node(|p| {
             if let Ok(s) = p.string_literal(level) {
+                p.style = Style::Literal;
                 Ok(R::new_rc(Yaml::Str(s)))
             } else if let Ok(s) = p.string_folded(level) {
+                p.style = Style::Folded;
                 Ok(R::new_rc(Yaml::Str(s)))
             } else {
                 p.seq(level, map)
@@ -224,16 +592,27 @@ impl<R: Repr> Loader<'_, R> {
         self.scalar_node(|p| p.scalar_term(level, flow))
     }
 
+    // The recursive parts are kept small, since the frames of the nested
+    // collections are stacked.
     fn scalar_node<F>(&mut self, f: F) -> PResult<Node<R>>
     where
         F: FnOnce(&mut Self) -> PResult<R::Rc>,
     {
+        let (anchor, tag) = self.node_props()?;
+        let pos = self.indicator();
+        let start = self.pos;
+        let yaml = f(self)?;
+        self.node_end(yaml, anchor, tag, pos, start)
+    }
+
+    /// Match the anchor and the tag before the node.
+    fn node_props(&mut self) -> PResult<(String, String)> {
         let mut anchor = self.anchor().unwrap_or_default();
         if !anchor.is_empty() {
             self.bound()?;
         }
         self.forward();
-        let tag = self.tag().unwrap_or_default();
+        let tag = self.tag().or_else(|e| e.or(|| Ok(String::new())))?;
         if !tag.is_empty() {
             self.bound()?;
         }
@@ -248,10 +627,58 @@ impl<R: Repr> Loader<'_, R> {
             }
         }
         self.forward();
-        let pos = self.indicator();
-        let yaml = f(self)?;
+        Ok((anchor, tag))
+    }
+
+    /// Create the node from the matched data started from `start`.
+    fn node_end(
+        &mut self,
+        yaml: R::Rc,
+        anchor: String,
+        tag: String,
+        mut pos: u64,
+        start: usize,
+    ) -> PResult<Node<R>> {
+        let yaml = match &*yaml {
+            Yaml::Str(s) if tag == concat!(tag_prefix!(), "binary") => match from_base64(s) {
+                Some(b) => R::new_rc(Yaml::Bytes(b)),
+                None => return self.err_span("binary", start, self.pos),
+            },
+            // Keep the plain text of the keywords and numbers
+            Yaml::Null | Yaml::Bool(_) | Yaml::Int(_) | Yaml::Float(_) | Yaml::Timestamp(_)
+                if tag == concat!(tag_prefix!(), "str") && !self.doc[start..].starts_with(b"*") =>
+            {
+                let s = String::from_utf8_lossy(&self.doc[start..self.pos]);
+                R::new_rc(Yaml::Str(s.trim_end().to_string()))
+            }
+            _ => yaml,
+        };
+        // The style is set by the last scalar
+        let style = core::mem::take(&mut self.style);
+        let is_collection = matches!(*yaml, Yaml::Seq(_) | Yaml::Map(_));
+        if is_collection {
+            // Block collections start from the first item
+            let mut cmt = false;
+            pos += self.doc[start..self.pos]
+                .iter()
+                .take_while(|&&c| {
+                    cmt = match c {
+                        b'#' => true,
+                        b'\n' | b'\r' => false,
+                        _ => cmt,
+                    };
+                    cmt || c.is_ascii_whitespace()
+                })
+                .count() as u64;
+        }
         self.forward();
-        let node = Node::new_repr(yaml, pos, &tag);
+        // Exclude the trailing white spaces
+        let text = &self.doc[start..self.pos];
+        let end = self.indicator() - (text.len() - text.trim_ascii_end().len()) as u64;
+        let mut node = Node::new_repr(yaml, pos, &tag).with_end(end);
+        if !is_collection {
+            node = node.with_style(style);
+        }
         if !anchor.is_empty()
             && self.anchors[self.doc_ind]
                 .insert(anchor, node.clone())
@@ -265,7 +692,20 @@ impl<R: Repr> Loader<'_, R> {
 
     /// Match flow scalar terminal.
     pub fn scalar_term(&mut self, level: usize, flow: bool) -> PResult<R::Rc> {
-        let yaml = if let Ok(s) = self.float() {
+        match self.scalar_atom(level, flow)? {
+            Some(yaml) => Ok(yaml),
+            None => self
+                .seq_flow(level)
+                .or_else(|e| e.or(|| self.map_flow(level)))
+                .or_else(|e| e.or(|| Ok(R::new_rc(Yaml::Null)))),
+        }
+    }
+
+    /// Match flow scalar terminal except the flow collections.
+    fn scalar_atom(&mut self, level: usize, flow: bool) -> PResult<Option<R::Rc>> {
+        let yaml = if let Ok(s) = self.timestamp() {
+            R::new_rc(Yaml::Timestamp(s))
+        } else if let Ok(s) = self.float() {
             R::new_rc(Yaml::Float(s))
         } else if let Ok(s) = self.sci_float() {
             R::new_rc(Yaml::Float(s))
@@ -277,28 +717,39 @@ impl<R: Repr> Loader<'_, R> {
             } else if let Some(node) = self.anchors[self.doc_ind].get(&s) {
                 node.clone_yaml()
             } else {
-                return self.err("anchor referenced before definition");
+                let start = self.pos - s.len() - 1;
+                return self.err_span("anchor referenced before definition", start, self.pos);
             }
-        } else if let Ok(s) = self.string_quoted(b'\'', b"''") {
+        } else if let Some(s) = self
+            .string_quoted(b'\'', b"''")
+            .map(Some)
+            .or_else(|e| e.or(|| Ok(None)))?
+        {
+            self.style = Style::SingleQuoted;
+            R::new_rc(Yaml::Str(s))
+        } else if let Some(s) = self
+            .string_quoted(b'"', b"\\\"")
+            .map(Some)
+            .or_else(|e| e.or(|| Ok(None)))?
+        {
+            self.style = Style::DoubleQuoted;
             R::new_rc(Yaml::Str(s))
-        } else if let Ok(s) = self.string_quoted(b'"', b"\\\"") {
-            R::new_rc(Yaml::Str(Parser::escape(&s)))
         } else if let Ok(s) = self.string_plain(level, flow) {
-            R::new_rc(match s.as_str() {
-                "~" | "null" | "Null" | "NULL" => Yaml::Null,
-                "true" | "True" | "TRUE" => Yaml::Bool(true),
-                "false" | "False" | "FALSE" => Yaml::Bool(false),
-                ".nan" | ".NaN" | ".NAN" => Yaml::Float("NaN".to_string()),
-                ".inf" | ".Inf" | ".INF" => Yaml::Float("inf".to_string()),
-                "-.inf" | "-.Inf" | "-.INF" => Yaml::Float("-inf".to_string()),
-                _ => Yaml::Str(s),
-            })
+            if self.bool_1_1 {
+                let b = match s.as_str() {
+                    "y" | "Y" | "yes" | "Yes" | "YES" | "on" | "On" | "ON" => Some(true),
+                    "n" | "N" | "no" | "No" | "NO" | "off" | "Off" | "OFF" => Some(false),
+                    _ => None,
+                };
+                if let Some(b) = b {
+                    return Ok(Some(R::new_rc(Yaml::Bool(b))));
+                }
+            }
+            R::new_rc(keyword(&s, self.undotted_float).unwrap_or(Yaml::Str(s)))
         } else {
-            self.seq_flow(level)
-                .or_else(|e| e.or(|| self.map_flow(level)))
-                .or_else(|e| e.or(|| Ok(R::new_rc(Yaml::Null))))?
+            return Ok(None);
         };
-        Ok(yaml)
+        Ok(Some(yaml))
     }
 
     /// Match flow sequence.
@@ -306,20 +757,28 @@ impl<R: Repr> Loader<'_, R> {
         self.sym(b'[')?;
         let mut v = vec![];
         loop {
-            self.inv(TakeOpt::More(0))?;
+            self.inv_comment()?;
             self.forward();
             if self.sym(b']').is_ok() {
                 break;
             }
             self.forward();
-            let n = self
-                .scalar(level + 1, false, true)
-                .or_else(|e| e.or(|| self.err("flow sequence item")))?;
-            v.push(n);
-            self.inv(TakeOpt::More(0))?;
-            if self.sym(b',').is_err() {
-                self.inv(TakeOpt::More(0))?;
-                self.sym(b']')?;
+            let n = if self.food().starts_with(b",") {
+                self.err("flow sequence item")
+            } else {
+                self.scalar(level + 1, false, true)
+                    .or_else(|e| e.or(|| self.err("flow sequence item")))
+            };
+            match n {
+                Ok(n) => v.push(n),
+                Err(e) => {
+                    v.push(self.recover(e, true)?);
+                    self.sym(b',').unwrap_or_default();
+                    continue;
+                }
+            }
+            self.inv_comment()?;
+            if self.flow_sep(b']', "flow sequence terminator")? {
                 break;
             }
         }
@@ -332,42 +791,89 @@ impl<R: Repr> Loader<'_, R> {
         self.sym(b'{')?;
         let mut m = vec![];
         loop {
-            self.inv(TakeOpt::More(0))?;
+            self.inv_comment()?;
             self.forward();
             if self.sym(b'}').is_ok() {
                 break;
             }
             self.forward();
-            let k = if self.complex_mapping().is_ok() {
+            let k = match self.map_flow_key(&m, level) {
+                Ok(k) => k,
+                Err(e) => {
+                    self.recover(e, true)?;
+                    self.sym(b',').unwrap_or_default();
+                    continue;
+                }
+            };
+            if matches!(self.food().first(), Some(b',' | b'}')) {
+                // Key without value, such as the set
+                let pos = self.indicator();
+                m.push((k, Node::new(Yaml::Null, pos, "")));
+            } else {
+                self.context(|p| p.bound().unwrap_or_default());
                 self.forward();
-                let k = self
+                let v = self
                     .scalar(level + 1, false, true)
-                    .or_else(|e| e.or(|| self.err("flow map key")))?;
-                if self.gap(true).is_ok() {
-                    self.ind(level)?;
+                    .or_else(|e| e.or(|| self.err("flow map value")));
+                match v {
+                    Ok(v) => m.push((k, v)),
+                    Err(e) => {
+                        m.push((k, self.recover(e, true)?));
+                        self.sym(b',').unwrap_or_default();
+                        continue;
+                    }
                 }
-                k
-            } else {
-                self.scalar_flow(level + 1, true)
-                    .or_else(|e| e.or(|| self.err("flow map key")))?
-            };
-            if self.sym(b':').is_err() {
-                return self.err("flow map splitter");
             }
-            self.context(|p| p.bound().unwrap_or_default());
-            self.forward();
-            let v = self
-                .scalar(level + 1, false, true)
-                .or_else(|e| e.or(|| self.err("flow map value")))?;
-            m.push((k, v));
-            if self.sym(b',').is_err() {
-                self.inv(TakeOpt::More(0))?;
-                self.sym(b'}')?;
+            if self.flow_sep(b'}', "flow map terminator")? {
                 break;
             }
         }
         self.forward();
-        Ok(R::new_rc(m.into_iter().collect()))
+        Ok(R::new_rc(Yaml::Map(merge_keys(m))))
+    }
+
+    /// Match the separator `,` or the terminator `close` of the flow
+    /// collections, return true if the collection is ended.
+    fn flow_sep(&mut self, close: u8, name: &'static str) -> PResult<bool> {
+        if self.sym(b',').is_ok() {
+            return Ok(false);
+        }
+        self.inv_comment()?;
+        if self.sym(close).is_ok() {
+            return Ok(true);
+        }
+        self.err(name).or_else(|e| self.recover(e, true))?;
+        if self.food().is_empty() {
+            return Ok(true);
+        }
+        self.sym(b',').unwrap_or_default();
+        Ok(false)
+    }
+
+    /// Match the key of the flow map and the splitter `:` if there is a
+    /// value.
+    fn map_flow_key(&mut self, m: &[(Node<R>, Node<R>)], level: usize) -> PResult<Node<R>> {
+        if self.food().starts_with(b",") {
+            return self.err("flow map key");
+        }
+        let k = if self.complex_mapping().is_ok() {
+            self.forward();
+            let k = self
+                .scalar(level + 1, false, true)
+                .or_else(|e| e.or(|| self.err("flow map key")))?;
+            if self.gap(true).is_ok() {
+                self.ind(level)?;
+            }
+            k
+        } else {
+            self.scalar_flow(level + 1, true)
+                .or_else(|e| e.or(|| self.err("flow map key")))?
+        };
+        self.check_key(m, &k)?;
+        if !matches!(self.food().first(), Some(b',' | b'}')) && self.sym(b':').is_err() {
+            return self.err("flow map splitter");
+        }
+        Ok(k)
     }
 
     /// Match sequence.
@@ -382,18 +888,24 @@ impl<R: Repr> Loader<'_, R> {
                     self.ind_define(level)?;
                 } else if self.gap(true).is_ok() {
                     // Root
-                    self.ind(level)?;
+                    self.ind_define_more(level)?;
                 }
-                self.sym(b'-')?;
-                self.bound()?;
+                self.seq_indicator(level)?;
             } else {
                 if self.gap(true).is_err() && !self.doc_end() {
-                    return self.err("sequence terminator");
+                    self.err("sequence terminator")
+                        .or_else(|e| self.recover(e, false))?;
+                    continue;
                 }
                 if self.doc_end() || self.ind(level).is_err() {
                     break;
                 }
-                if self.sym(b'-').is_err() || self.bound().is_err() {
+                let extra = self.food().iter().take_while(|&&c| c == b' ').count();
+                if extra > 0 && self.food()[extra..].starts_with(b"-") {
+                    self.err_indent(extra).or_else(|e| self.recover(e, false))?;
+                    continue;
+                }
+                if self.seq_indicator(level).is_err() {
                     break;
                 }
                 self.forward();
@@ -401,7 +913,8 @@ impl<R: Repr> Loader<'_, R> {
             self.forward();
             let n = self
                 .scalar(level + 1, false, false)
-                .or_else(|e| e.or(|| self.err("sequence item")))?;
+                .or_else(|e| e.or(|| self.err("sequence item")))
+                .or_else(|e| self.recover(e, false))?;
             v.push(n);
         }
         // Keep last wrapping
@@ -409,76 +922,259 @@ impl<R: Repr> Loader<'_, R> {
         Ok(R::new_rc(v.into_iter().collect()))
     }
 
+    /// Match the sequence indicator `-`, the compact collection behind it
+    /// is indented by the width of the indicator and the spaces.
+    fn seq_indicator(&mut self, level: usize) -> PResult<()> {
+        self.sym(b'-')?;
+        let width = self.count(|p| p.bound())? + 1;
+        if !matches!(self.food().first(), None | Some(b'\n' | b'\r' | b'#')) {
+            self.ind_compact(level + 1, width);
+        }
+        Ok(())
+    }
+
     /// Match map.
     pub fn map(&mut self, level: usize, map: bool, flow: bool) -> PResult<R::Rc> {
         let mut m = vec![];
         loop {
             self.forward();
-            let k = if m.is_empty() {
+            let (k, complex) = if m.is_empty() {
                 // First item
                 if map {
+                    // The nested map should be indented more than its parent
                     self.gap(true)?;
-                    self.ind_define(level)?;
+                    self.ind_define_more(level)?;
                 } else if self.gap(true).is_ok() {
                     // Root
-                    self.ind(level)?;
+                    self.ind_define_more(level)?;
                 }
                 self.forward();
-                let k = if self.complex_mapping().is_ok() {
-                    self.forward();
-                    let k = self
-                        .scalar(level + 1, true, flow)
-                        .or_else(|e| e.or(|| self.err("map key")))?;
-                    if self.gap(true).is_ok() {
-                        self.ind(level)?;
-                    }
-                    k
+                if self.complex_mapping().is_ok() {
+                    (self.complex_key(level, flow)?, true)
                 } else {
-                    self.scalar_flow(level + 1, flow)?
-                };
-                if self.sym(b':').is_err() || self.bound().is_err() {
-                    // Return key
-                    return Ok(k.clone_yaml());
+                    // The same level as the scalar if it is not a key
+                    let start = self.pos;
+                    let k = self.scalar_flow(level, flow)?;
+                    if self.sym(b':').is_err() || self.bound().is_err() {
+                        // Return key
+                        self.style = k.style();
+                        return Ok(k.clone_yaml());
+                    }
+                    if self.doc[start..self.pos].contains(&b'\n') {
+                        // Implicit key is limited to a single line
+                        return self.err_span("map key", start, self.pos);
+                    }
+                    (k, false)
                 }
-                k
             } else {
                 if self.gap(true).is_err() && !self.doc_end() {
-                    return self.err("map terminator");
+                    self.err("map terminator")
+                        .or_else(|e| self.recover(e, false))?;
+                    continue;
                 }
                 if self.doc_end() || self.ind(level).is_err() {
                     break;
                 }
+                let extra = self.food().iter().take_while(|&&c| c == b' ').count();
+                if extra > 0 {
+                    self.err_indent(extra).or_else(|e| self.recover(e, false))?;
+                    continue;
+                }
                 self.forward();
-                let k = if self.complex_mapping().is_ok() {
-                    self.forward();
-                    let k = self
-                        .scalar(level + 1, true, flow)
-                        .or_else(|e| e.or(|| self.err("map key")))?;
-                    if self.gap(true).is_ok() {
-                        self.ind(level)?;
+                match self.map_key(&m, level, flow) {
+                    Ok(k) => k,
+                    Err(e) => {
+                        self.recover(e, false)?;
+                        continue;
                     }
-                    k
-                } else {
-                    self.scalar_flow(level + 1, flow)
-                        .or_else(|e| e.or(|| self.err("map key")))?
-                };
-                if self.sym(b':').is_err() || self.bound().is_err() {
-                    return self.err("map splitter");
                 }
-                k
             };
+            if complex && !self.complex_splitter(level) {
+                // Explicit key without value
+                let pos = self.indicator();
+                m.push((k, Node::new(Yaml::Null, pos, "")));
+                continue;
+            }
             self.forward();
+            let compact = complex && self.is_compact();
             let v = self
-                .scalar(level + 1, true, false)
-                .or_else(|e| e.or(|| self.err("map value")))?;
+                .scalar(level + 1, !compact, false)
+                .or_else(|e| e.or(|| self.err("map value")))
+                .or_else(|e| self.recover(e, false))?;
             m.push((k, v));
         }
         // Keep last wrapping
         self.backward();
-        Ok(R::new_rc(m.into_iter().collect()))
+        Ok(R::new_rc(Yaml::Map(merge_keys(m))))
+    }
+
+    /// Match the key of the block map except the first one, return the key
+    /// and whether it is a complex key.
+    fn map_key(
+        &mut self,
+        m: &[(Node<R>, Node<R>)],
+        level: usize,
+        flow: bool,
+    ) -> PResult<(Node<R>, bool)> {
+        if self.complex_mapping().is_ok() {
+            let k = self.complex_key(level, flow)?;
+            self.check_key(m, &k)?;
+            Ok((k, true))
+        } else {
+            let k = self
+                .scalar_flow(level + 1, flow)
+                .or_else(|e| e.or(|| self.err("map key")))?;
+            self.check_key(m, &k)?;
+            if self.sym(b':').is_err() || self.bound().is_err() {
+                return self.err("map splitter");
+            }
+            Ok((k, false))
+        }
+    }
+
+    /// Raise "indentation" error on the extra spaces at the current position,
+    /// the expected and found columns are shown in the message.
+    fn err_indent<T>(&self, extra: usize) -> PResult<T> {
+        match self.err_span("indentation", self.pos, self.pos + extra) {
+            Err(PError::Terminate { name, pos, end, msg }) => {
+                let (_, column) = pos_to_line_col(self.doc, pos);
+                let found = column + extra;
+                let msg = format!("{msg}\n\nexpected column {column}, found {found}");
+                Err(PError::Terminate { name, pos, end, msg })
+            }
+            r => r,
+        }
+    }
+
+    /// Raise error if the key is already in the map under the strict keys
+    /// mode. The key should be just matched.
+    fn check_key(&self, m: &[(Node<R>, Node<R>)], k: &Node<R>) -> PResult<()> {
+        if !self.strict_keys {
+            return Ok(());
+        }
+        let first = match m.iter().find(|(k1, _)| k1 == k) {
+            Some((first, _)) => first.pos(),
+            None => return Ok(()),
+        };
+        let offset = self.indicator() - self.pos as u64;
+        let start = (k.pos() - offset) as usize;
+        match self.err_span("duplicated key", start, self.pos) {
+            Err(PError::Terminate { name, pos, end, msg }) => {
+                let (line, column) = pos_to_line_col(self.doc, first);
+                let msg = format!("{msg}\n\nfirst defined at {line}:{column}");
+                Err(PError::Terminate { name, pos, end, msg })
+            }
+            r => r,
+        }
+    }
+
+    /// Match the key behind the complex mapping indicator `?`.
+    fn complex_key(&mut self, level: usize, flow: bool) -> PResult<Node<R>> {
+        self.forward();
+        let compact = self.is_compact();
+        self.scalar(level + 1, !compact, flow)
+            .or_else(|e| e.or(|| self.err("map key")))
+    }
+
+    /// Match the map splitter `:` of the complex key, which can be placed at
+    /// the next line. Nothing is consumed if mismatched.
+    fn complex_splitter(&mut self, level: usize) -> bool {
+        self.context(|p| {
+            if p.gap(true).is_ok() && p.ind(level).is_err() {
+                p.backward();
+                return false;
+            }
+            let b = p.sym(b':').is_ok() && p.bound().is_ok();
+            if !b {
+                p.backward();
+            }
+            b
+        })
+    }
+
+    /// Return true if the compact sequence is placed at the same line,
+    /// such as `? - a`. Nothing is consumed.
+    fn is_compact(&mut self) -> bool {
+        self.context(|p| {
+            let b = p.sym(b'-').is_ok() && p.bound().is_ok();
+            p.backward();
+            b
+        })
     }
 }
 
+/// Collect the map items and merge the values of the merge key `<<`.
+///
+/// Resolve the keywords of the plain scalar, such as null, booleans and the
+/// special floats. The special floats without the dot are recognized if
+/// `undotted` is true.
+pub(crate) fn keyword<R: Repr>(s: &str, undotted: bool) -> Option<Yaml<R>> {
+    let t = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if undotted && matches!(t, "inf" | "Inf" | "INF" | "nan" | "NaN" | "NAN") {
+        let (sign, t) = s.split_at(s.len() - 3);
+        return keyword(&format!("{sign}.{t}"), false);
+    }
+    let yaml = match s {
+        "~" | "null" | "Null" | "NULL" => Yaml::Null,
+        "true" | "True" | "TRUE" => Yaml::Bool(true),
+        "false" | "False" | "FALSE" => Yaml::Bool(false),
+        ".nan" | ".NaN" | ".NAN" => Yaml::Float("NaN".to_string()),
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => Yaml::Float("inf".to_string()),
+        "-.inf" | "-.Inf" | "-.INF" => Yaml::Float("-inf".to_string()),
+        _ => return None,
+    };
+    Some(yaml)
+}
+
+/// The value of the merge key should be a map or a sequence of maps,
+/// the explicit keys take precedence over the merged keys, and the former maps
+/// take precedence over the latter ones in the sequence. Otherwise, the merge
+/// key is treated as a normal key, such as the unresolved aliases.
+fn merge_keys<R: Repr>(m: Vec<(Node<R>, Node<R>)>) -> Map<R> {
+    let is_merge = |(k, v): &(Node<R>, Node<R>)| {
+        matches!(k.yaml(), Yaml::Str(s) if s == "<<")
+            && [
+                concat!(tag_prefix!(), "str"),
+                concat!(tag_prefix!(), "merge"),
+            ]
+            .contains(&k.tag())
+            && match v.yaml() {
+                Yaml::Map(_) => true,
+                Yaml::Seq(v) => v.iter().all(|n| matches!(n.yaml(), Yaml::Map(_))),
+                _ => false,
+            }
+    };
+    if !m.iter().any(is_merge) {
+        return m.into_iter().collect();
+    }
+    let explicit = m
+        .iter()
+        .filter(|item| !is_merge(item))
+        .cloned()
+        .collect::<Map<R>>();
+    let mut map = Map::new();
+    for item in m {
+        if !is_merge(&item) {
+            map.insert(item.0, item.1);
+            continue;
+        }
+        let sources = match item.1.yaml() {
+            Yaml::Seq(v) => v.clone(),
+            _ => vec![item.1.clone()],
+        };
+        for n in sources {
+            if let Yaml::Map(src) = n.yaml() {
+                for (k, v) in src {
+                    if !explicit.contains_key(k) && !map.contains_key(k) {
+                        map.insert(k.clone(), v.clone());
+                    }
+                }
+            }
+        }
+    }
+    map
+}
+
 impl<'a, R: Repr> Deref for Loader<'a, R> {
     type Target = Parser<'a>;
 
@@ -525,6 +1221,21 @@ pub fn parse<R: Repr>(doc: &str) -> Result<Seq<R>, PError> {
     Loader::new(doc.as_bytes()).parse()
 }
 
+/// Parse a stream which only contains one YAML document, see
+/// [`Loader::parse_single`].
+///
+/// ```
+/// use yaml_peg::{node, parse_single, parser::ErrorKind, repr::RcRepr};
+///
+/// let n = parse_single::<RcRepr>("a: b\n...\n").unwrap();
+/// assert_eq!(n, node!({"a" => "b"}));
+/// let e = parse_single::<RcRepr>("a\n---\nb").unwrap_err();
+/// assert_eq!(e.kind(), Some(ErrorKind::DocumentSplitter));
+/// ```
+pub fn parse_single<R: Repr>(doc: &str) -> Result<Node<R>, PError> {
+    Loader::new(doc.as_bytes()).parse_single()
+}
+
 /// Parse cyclic YAML document into [`alloc::rc::Rc`] or [`alloc::sync::Arc`]
 /// data holder. Return an sequence of nodes and keep the anchors placeholder.
 ///
@@ -543,3 +1254,25 @@ pub fn parse_cyclic<R: Repr>(doc: &str) -> Result<(Seq<R>, Vec<Anchors<R>>), PEr
     let mut loader = Loader::new(doc.as_bytes()).cyclic_mode(true);
     loader.parse().map(|root| (root, loader.get_anchors()))
 }
+
+/// Read the entire stream from a reader then parse it into
+/// [`alloc::rc::Rc`] or [`alloc::sync::Arc`] data holder.
+///
+/// The parser error and the invalid UTF-8 stream are returned as
+/// [`std::io::ErrorKind::InvalidData`].
+///
+/// ```
+/// use std::io::Cursor;
+/// use yaml_peg::{node, parse_reader};
+///
+/// let reader = Cursor::new(b"a: b".to_vec());
+/// let root = parse_reader(reader).unwrap();
+/// assert_eq!(root, vec![node!({"a" => "b"})]);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub fn parse_reader<R: Repr, Rd: std::io::Read>(mut reader: Rd) -> std::io::Result<Seq<R>> {
+    let mut doc = String::new();
+    reader.read_to_string(&mut doc)?;
+    Ok(parse(&doc)?)
+}
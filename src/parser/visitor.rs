@@ -0,0 +1,93 @@
+use super::*;
+
+/// The event handler of [`Loader::parse_events`].
+///
+/// The methods are called in the document order, and the keys and values of
+/// a map are visited alternately. All methods do nothing by default.
+///
+/// ```
+/// use yaml_peg::{parser::{Loader, Visitor}, repr::RcRepr, Node};
+///
+/// #[derive(Default)]
+/// struct Depth {
+///     depth: usize,
+///     max: usize,
+/// }
+///
+/// impl Visitor<RcRepr> for Depth {
+///     fn seq_start(&mut self, _: &Node<RcRepr>) {
+///         self.depth += 1;
+///         self.max = self.max.max(self.depth);
+///     }
+///
+///     fn seq_end(&mut self) {
+///         self.depth -= 1;
+///     }
+/// }
+///
+/// let mut v = Depth::default();
+/// Loader::<RcRepr>::new(b"[[1], [[2]]]").parse_events(&mut v).unwrap();
+/// assert_eq!(3, v.max);
+/// ```
+pub trait Visitor<R: Repr> {
+    /// Start of a document.
+    fn doc_start(&mut self) {}
+
+    /// End of a document.
+    fn doc_end(&mut self) {}
+
+    /// A scalar node.
+    fn scalar(&mut self, _node: &Node<R>) {}
+
+    /// Start of a sequence, the node contains the items.
+    fn seq_start(&mut self, _node: &Node<R>) {}
+
+    /// End of a sequence.
+    fn seq_end(&mut self) {}
+
+    /// Start of a map, the node contains the items.
+    fn map_start(&mut self, _node: &Node<R>) {}
+
+    /// End of a map.
+    fn map_end(&mut self) {}
+
+    /// An alias, only appeared in the cyclic mode.
+    fn alias(&mut self, _name: &str, _node: &Node<R>) {}
+}
+
+impl<R: Repr> Loader<'_, R> {
+    /// Parse the documents and send the events to the visitor.
+    ///
+    /// The documents are parsed one by one, so the tree of a document is
+    /// dropped after visiting. If an error occurred, the events of the
+    /// previous documents are already sent.
+    pub fn parse_events<V: Visitor<R>>(&mut self, v: &mut V) -> PResult<()> {
+        for node in self.documents() {
+            let node = node?;
+            v.doc_start();
+            visit(&node, v);
+            v.doc_end();
+        }
+        Ok(())
+    }
+}
+
+fn visit<R: Repr, V: Visitor<R>>(node: &Node<R>, v: &mut V) {
+    match node.yaml() {
+        Yaml::Seq(s) => {
+            v.seq_start(node);
+            s.iter().for_each(|n| visit(n, v));
+            v.seq_end();
+        }
+        Yaml::Map(m) => {
+            v.map_start(node);
+            for (k, n) in m {
+                visit(k, v);
+                visit(n, v);
+            }
+            v.map_end();
+        }
+        Yaml::Alias(a) => v.alias(a, node),
+        _ => v.scalar(node),
+    }
+}
@@ -21,6 +21,12 @@ pub trait Repr: Sized {
 
     /// The creation function of this type.
     fn new_rc(yaml: Yaml<Self>) -> Self::Rc;
+
+    /// Get the mutable reference of the data, clone the data if it is shared.
+    fn make_mut(rc: &mut Self::Rc) -> &mut Yaml<Self>;
+
+    /// Take the data out, clone the data if it is shared.
+    fn into_inner(rc: Self::Rc) -> Yaml<Self>;
 }
 
 impl Repr for RcRepr {
@@ -29,6 +35,14 @@ impl Repr for RcRepr {
     fn new_rc(yaml: Yaml<Self>) -> Self::Rc {
         Rc::new(yaml)
     }
+
+    fn make_mut(rc: &mut Self::Rc) -> &mut Yaml<Self> {
+        Rc::make_mut(rc)
+    }
+
+    fn into_inner(rc: Self::Rc) -> Yaml<Self> {
+        Rc::try_unwrap(rc).unwrap_or_else(|rc| (*rc).clone())
+    }
 }
 
 impl Repr for ArcRepr {
@@ -37,4 +51,12 @@ impl Repr for ArcRepr {
     fn new_rc(yaml: Yaml<Self>) -> Self::Rc {
         Arc::new(yaml)
     }
+
+    fn make_mut(rc: &mut Self::Rc) -> &mut Yaml<Self> {
+        Arc::make_mut(rc)
+    }
+
+    fn into_inner(rc: Self::Rc) -> Yaml<Self> {
+        Arc::try_unwrap(rc).unwrap_or_else(|rc| (*rc).clone())
+    }
 }
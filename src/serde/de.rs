@@ -2,7 +2,7 @@ use super::SerdeError;
 use crate::{
     parse,
     repr::{RcRepr, Repr},
-    to_f64, to_i64, Map, Node, Seq, Yaml,
+    to_f64, to_i128, to_i64, to_u128, Map, Node, Seq, Yaml,
 };
 use alloc::{format, string::ToString, vec::Vec};
 use core::marker::PhantomData;
@@ -11,7 +11,7 @@ use serde::{
         DeserializeOwned, DeserializeSeed, EnumAccess, Error, Expected, MapAccess, SeqAccess,
         Unexpected, VariantAccess, Visitor,
     },
-    serde_if_integer128, Deserialize, Deserializer,
+    Deserialize, Deserializer,
 };
 
 macro_rules! impl_visitor {
@@ -28,13 +28,27 @@ macro_rules! impl_visitor {
 }
 
 macro_rules! impl_deserializer {
-    ($(fn $method:ident($ty:ident) => $visit:ident($n:ident => $value:expr))+) => {
+    ($(fn $method:ident($($ty:ident)|+) => $visit:ident($n:ident => $value:expr))+) => {
         $(fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'a>,
         {
             match self.yaml() {
-                Yaml::$ty($n) => visitor.$visit($value),
+                $(Yaml::$ty($n))|+ => visitor.$visit($value),
+                _ => Err(unexpected(&self, visitor)),
+            }
+        })+
+    };
+}
+
+macro_rules! impl_int_deserializer {
+    ($(fn $method:ident => $visit:ident)+) => {
+        $(fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'a>,
+        {
+            match self.yaml() {
+                Yaml::Int(n) => visitor.$visit(int(&self, n)?),
                 _ => Err(unexpected(&self, visitor)),
             }
         })+
@@ -77,6 +91,67 @@ where
     root.into_iter().map(D::deserialize).collect()
 }
 
+/// Deserialize a parsed node to a specific type.
+///
+/// The node will be cloned, so the node can be reused after the conversion.
+/// The numbers are converted from their string representation.
+///
+/// ```
+/// use serde::Deserialize;
+/// use std::collections::HashMap;
+/// use yaml_peg::{parse, repr::RcRepr, serde::from_node};
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// enum Level {
+///     Debug,
+///     Limit(u8),
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+///     timeout: Option<f64>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     server: Server,
+///     paths: Vec<String>,
+///     env: HashMap<String, i32>,
+///     level: Level,
+///     backup: Option<Server>,
+/// }
+///
+/// let doc = "
+/// server:
+///   host: localhost
+///   port: 8080
+///   timeout: 30
+/// paths: [/usr, /opt]
+/// env: {a: 1, b: -2}
+/// level:
+///   Limit: 3
+/// backup: ~
+/// ";
+/// let root = parse::<RcRepr>(doc).unwrap();
+/// let config = from_node::<Config, _>(&root[0]).unwrap();
+/// assert_eq!("localhost", config.server.host);
+/// assert_eq!(8080, config.server.port);
+/// assert_eq!(Some(30.), config.server.timeout);
+/// assert_eq!(vec!["/usr", "/opt"], config.paths);
+/// assert_eq!(-2, config.env["b"]);
+/// assert_eq!(Level::Limit(3), config.level);
+/// assert!(config.backup.is_none());
+/// ```
+pub fn from_node<D, R>(node: &Node<R>) -> Result<D, SerdeError>
+where
+    D: DeserializeOwned,
+    R: Repr,
+{
+    D::deserialize(node.clone())
+}
+
 struct NodeVisitor<R: Repr>(PhantomData<R>);
 
 impl<'a, R: Repr> Visitor<'a> for NodeVisitor<R> {
@@ -279,9 +354,14 @@ impl<'a, R: Repr> Deserializer<'a> for Node<R> {
         match self.yaml() {
             Yaml::Null => visitor.visit_unit(),
             Yaml::Bool(b) => visitor.visit_bool(*b),
-            Yaml::Int(n) => visitor.visit_i64(to_i64(n).unwrap()),
-            Yaml::Float(n) => visitor.visit_f64(to_f64(n).unwrap()),
-            Yaml::Str(s) => visitor.visit_str(s),
+            Yaml::Int(n) => match (to_i64(n), to_u128(n).map(u64::try_from)) {
+                (Ok(n), _) => visitor.visit_i64(n),
+                (_, Ok(Ok(n))) => visitor.visit_u64(n),
+                _ => visitor.visit_f64(float(&self, n)?),
+            },
+            Yaml::Float(n) => visitor.visit_f64(float(&self, n)?),
+            Yaml::Str(s) | Yaml::Timestamp(s) => visitor.visit_str(s),
+            Yaml::Bytes(b) => visitor.visit_bytes(b),
             Yaml::Seq(v) => visitor.visit_seq(SeqVisitor::from(v.clone())),
             Yaml::Map(m) => visitor.visit_map(MapVisitor::from(m.clone())),
             Yaml::Alias(a) => Err(SerdeError::from(format!("anchor {a}")).pos(self.pos())),
@@ -290,28 +370,44 @@ impl<'a, R: Repr> Deserializer<'a> for Node<R> {
 
     impl_deserializer! {
         fn deserialize_bool(Bool) => visit_bool(v => *v)
-        fn deserialize_i8(Int) => visit_i8(n => to_i64(n).unwrap() as i8)
-        fn deserialize_i16(Int) => visit_i16(n => to_i64(n).unwrap() as i16)
-        fn deserialize_i32(Int) => visit_i32(n => to_i64(n).unwrap() as i32)
-        fn deserialize_i64(Int) => visit_i64(n => to_i64(n).unwrap())
-        fn deserialize_u8(Int) => visit_u8(n => to_i64(n).unwrap() as u8)
-        fn deserialize_u16(Int) => visit_u16(n => to_i64(n).unwrap() as u16)
-        fn deserialize_u32(Int) => visit_u32(n => to_i64(n).unwrap() as u32)
-        fn deserialize_u64(Int) => visit_u64(n => to_i64(n).unwrap() as u64)
-        fn deserialize_f32(Float) => visit_f32(n => to_f64(n).unwrap() as f32)
-        fn deserialize_f64(Float) => visit_f64(n => to_f64(n).unwrap())
-        fn deserialize_str(Str) => visit_str(s => s)
-        fn deserialize_string(Str) => visit_str(s => s)
+        fn deserialize_str(Str | Timestamp) => visit_str(s => s)
+        fn deserialize_string(Str | Timestamp) => visit_str(s => s)
         fn deserialize_char(Str) => visit_str(s => s)
         fn deserialize_seq(Seq) => visit_seq(a => SeqVisitor::from(a.clone()))
         fn deserialize_map(Map) => visit_map(m => MapVisitor::from(m.clone()))
         fn deserialize_identifier(Str) => visit_str(s => s)
     }
 
-    serde_if_integer128! {
-        impl_deserializer! {
-            fn deserialize_i128(Int) => visit_i128(n => to_i64(n).unwrap() as i128)
-            fn deserialize_u128(Int) => visit_u128(n => to_i64(n).unwrap() as u128)
+    impl_int_deserializer! {
+        fn deserialize_i8 => visit_i8
+        fn deserialize_i16 => visit_i16
+        fn deserialize_i32 => visit_i32
+        fn deserialize_i64 => visit_i64
+        fn deserialize_i128 => visit_i128
+        fn deserialize_u8 => visit_u8
+        fn deserialize_u16 => visit_u16
+        fn deserialize_u32 => visit_u32
+        fn deserialize_u64 => visit_u64
+        fn deserialize_u128 => visit_u128
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'a>,
+    {
+        match self.yaml() {
+            Yaml::Int(n) | Yaml::Float(n) => visitor.visit_f32(float(&self, n)? as f32),
+            _ => Err(unexpected(&self, visitor)),
+        }
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'a>,
+    {
+        match self.yaml() {
+            Yaml::Int(n) | Yaml::Float(n) => visitor.visit_f64(float(&self, n)?),
+            _ => Err(unexpected(&self, visitor)),
         }
     }
 
@@ -328,6 +424,7 @@ impl<'a, R: Repr> Deserializer<'a> for Node<R> {
     {
         match self.yaml() {
             Yaml::Str(s) => visitor.visit_str(s),
+            Yaml::Bytes(b) => visitor.visit_bytes(b),
             Yaml::Seq(v) => visitor.visit_seq(&mut SeqVisitor::from(v.clone())),
             _ => Err(unexpected(&self, visitor)),
         }
@@ -445,14 +542,46 @@ impl<'a, R: Repr> Deserializer<'a> for Node<R> {
     }
 }
 
+fn int<R, T>(node: &Node<R>, n: &str) -> Result<T, SerdeError>
+where
+    R: Repr,
+    T: TryFrom<i128> + TryFrom<u128>,
+{
+    match to_i128(n) {
+        Ok(n) => T::try_from(n).ok(),
+        Err(_) => to_u128(n).ok().and_then(|n| T::try_from(n).ok()),
+    }
+    .ok_or_else(|| {
+        SerdeError::invalid_value(Unexpected::Other(n), &"an integer in range").pos(node.pos())
+    })
+}
+
+fn float<R: Repr>(node: &Node<R>, n: &str) -> Result<f64, SerdeError> {
+    to_f64(n)
+        .ok()
+        .or_else(|| to_i128(n).ok().map(|n| n as f64))
+        .or_else(|| to_u128(n).ok().map(|n| n as f64))
+        .ok_or_else(|| {
+            SerdeError::invalid_value(Unexpected::Other(n), &"a floating point number")
+                .pos(node.pos())
+        })
+}
+
 #[cold]
 fn unexpected<R: Repr>(node: &Node<R>, exp: impl Expected) -> SerdeError {
     let ty = match node.yaml() {
         Yaml::Null => Unexpected::Unit,
         Yaml::Bool(b) => Unexpected::Bool(*b),
-        Yaml::Int(n) => Unexpected::Signed(to_i64(n).unwrap()),
-        Yaml::Float(n) => Unexpected::Float(to_f64(n).unwrap()),
-        Yaml::Str(s) => Unexpected::Str(s),
+        Yaml::Int(n) => match to_i64(n) {
+            Ok(n) => Unexpected::Signed(n),
+            Err(_) => Unexpected::Other(n),
+        },
+        Yaml::Float(n) => match to_f64(n) {
+            Ok(n) => Unexpected::Float(n),
+            Err(_) => Unexpected::Other(n),
+        },
+        Yaml::Str(s) | Yaml::Timestamp(s) => Unexpected::Str(s),
+        Yaml::Bytes(b) => Unexpected::Bytes(b),
         Yaml::Seq(_) => Unexpected::Seq,
         Yaml::Map(_) => Unexpected::Map,
         Yaml::Alias(_) => Unexpected::Other("anchor"),
@@ -31,7 +31,19 @@
 //!
 //! For converting custom data into YAML data, please see [`to_node`] and
 //! [`to_arc_node`], and if you went to parse / dump YAML document, use
-//! [`from_str`] and [`to_string`].
+//! [`from_str`] and [`to_string`]. The parsed nodes can be converted by
+//! [`from_node`].
+//!
+//! [`crate::Node`] and [`crate::Yaml`] are also serializable, so the parsed
+//! data can be converted into other formats which are supported by [`serde`].
+//! The numbers are parsed from their string representation, and the integers
+//! out of [`i64`] range are converted into floating point numbers.
+//!
+//! # JSON
+//!
+//! Since the nodes are serializable, `serde_json::to_value` can convert them
+//! into JSON values. With the `json` feature, `Node::to_json_value`
+//! does the same thing without the serde protocol.
 //!
 //! # Anchors
 //!
@@ -1,4 +1,4 @@
-use crate::{repr::Repr, to_f64, to_i64, Node, Yaml};
+use crate::{big_int_f64, repr::Repr, to_f64, to_i128, to_i64, to_u128, Node, Yaml};
 use alloc::format;
 use serde::{
     ser::{Error as _, SerializeMap as _},
@@ -10,12 +10,33 @@ impl<R: Repr> Serialize for Node<R> {
     where
         S: Serializer,
     {
-        match self.yaml() {
+        self.yaml().serialize(serializer)
+    }
+}
+
+impl<R: Repr> Serialize for Yaml<R> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
             Yaml::Null => serializer.serialize_unit(),
             Yaml::Bool(b) => serializer.serialize_bool(*b),
-            Yaml::Int(n) => serializer.serialize_i64(to_i64(n).unwrap()),
-            Yaml::Float(n) => serializer.serialize_f64(to_f64(n).unwrap()),
-            Yaml::Str(s) => serializer.serialize_str(s),
+            // Out of range integers are represented as floating point numbers
+            Yaml::Int(n) => {
+                if let Ok(n) = to_i64(n) {
+                    serializer.serialize_i64(n)
+                } else if let Ok(n) = to_i128(n) {
+                    serializer.serialize_i128(n)
+                } else if let Ok(n) = to_u128(n) {
+                    serializer.serialize_u128(n)
+                } else {
+                    serializer.serialize_f64(big_int_f64(n))
+                }
+            }
+            Yaml::Float(n) => serializer.serialize_f64(to_f64(n).map_err(S::Error::custom)?),
+            Yaml::Str(s) | Yaml::Timestamp(s) => serializer.serialize_str(s),
+            Yaml::Bytes(b) => serializer.serialize_bytes(b),
             Yaml::Seq(v) => v.serialize(serializer),
             Yaml::Map(m) => {
                 let mut map = serializer.serialize_map(Some(m.len()))?;
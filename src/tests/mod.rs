@@ -1,4 +1,5 @@
 use crate::{parser::PError, *};
+use alloc::{format, string::ToString, vec, vec::Vec};
 
 fn show_err<E>(e: PError) -> E {
     panic!("{}", e)
@@ -67,7 +68,7 @@ fn test_yaml() {
                     f64::NAN,
                     -f64::INFINITY,
                     "-.infs",
-                    "2001-11-23 15:01:42 -5",
+                    node!(Yaml::Timestamp("2001-11-23 15:01:42 -5".into())),
                     "https://www.google.com/",
                 ]),
             }),
@@ -83,7 +84,7 @@ fn test_yaml() {
             node!({"a4" => ()}) => "b3, b4",
             "test multiline" => node!([
                 node!({
-                    "folded" => "aaa{}[] bbb ccc\nddd\n# eee\n",
+                    "folded" => "aaa{}[] bbb\n  ccc\n\n  ddd\n\n# eee\n",
                     "literal" => "aaa{}[]\nbbb\n  ccc\n\n  ddd\n\n# eee\n",
                 }),
                 node!({
@@ -184,3 +185,2464 @@ fn test_anchor() {
         ])
     );
 }
+
+#[test]
+fn test_literal() {
+    const DOC: &str = include_str!("literal.yaml");
+    let mut root = parse(DOC).unwrap_or_else(show_err);
+    let node = root.remove(0);
+    assert_eq!(
+        node,
+        node!({
+            "indented" => "line one\n\nline two\n",
+            "nested" => node!({
+                "text" => "four\n five\n",
+                "next" => "a",
+            }),
+            "leading" => "\nafter empty line\n",
+            "end" => "dedent\n",
+            "last" => "b",
+        })
+    );
+}
+
+#[test]
+fn test_folded() {
+    const DOC: &str = include_str!("folded.yaml");
+    let mut root = parse(DOC).unwrap_or_else(show_err);
+    let node = root.remove(0);
+    assert_eq!(
+        node,
+        node!({
+            "paragraph" => "Lorem ipsum dolor sit amet.\nSecond paragraph.\n",
+            "code" => "Example:\n  fn main() {\n      println!();\n  }\nEnd of code.\n",
+        })
+    );
+}
+
+#[test]
+fn test_chomp() {
+    const DOC: &str = include_str!("chomp.yaml");
+    let mut root = parse(DOC).unwrap_or_else(show_err);
+    let node = root.remove(0);
+    assert_eq!(
+        node,
+        node!({
+            "clip" => "text  \n  more\n",
+            "strip" => "text  \n  more",
+            "keep" => "text  \n  more\n\n\n",
+            "strip indent" => "  text",
+            "keep indent" => " text\n\n",
+            "folded strip" => " text\n more",
+        })
+    );
+}
+
+#[test]
+fn test_seq() {
+    const DOC: &str = include_str!("seq.yaml");
+    let mut root = parse(DOC).unwrap_or_else(show_err);
+    let node = root.remove(0);
+    assert_eq!(
+        node,
+        node!([
+            1,
+            (),
+            node!({"a" => 1, "b" => 2}),
+            node!(["x", node!(["y"])]),
+            node!([()]),
+            2.5,
+            3,
+        ])
+    );
+}
+
+#[test]
+fn test_map() {
+    const DOC: &str = include_str!("map.yaml");
+    let mut root = parse(DOC).unwrap_or_else(show_err);
+    let node = root.remove(0);
+    assert_eq!(
+        node,
+        node!({
+            "a" => node!({"b" => node!({"c" => 1}), "d" => node!([2])}),
+            "e" => 3,
+            "f" => node!(["x", "y"]),
+            "g with space" => "h i",
+            "last" => (),
+        })
+    );
+    let keys = node.as_map().unwrap().keys().cloned().collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        node!(["a", "e", "f", "g with space", "last"])
+            .as_seq()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_merge() {
+    const DOC: &str = include_str!("merge.yaml");
+    let mut root = parse(DOC).unwrap_or_else(show_err);
+    let node = root.remove(0);
+    let derived = node.get("derived").unwrap().as_map().unwrap();
+    assert_eq!(
+        derived.into_iter().collect::<Vec<_>>(),
+        [
+            (node!("name"), node!("base")),
+            (node!("size"), node!(10)),
+            (node!("owner"), node!("me")),
+        ]
+    );
+    assert_eq!(
+        node["multiple"],
+        node!({"size" => 1, "color" => "red", "name" => "multiple"})
+    );
+}
+
+#[test]
+fn test_comment() {
+    const DOC: &str = include_str!("comment.yaml");
+    let mut root = parse(DOC).unwrap_or_else(show_err);
+    let node = root.remove(0);
+    assert_eq!(
+        node,
+        node!({
+            "int" => 1,
+            "float" => 2.5,
+            "quoted" => "a # b",
+            "plain" => "c#d",
+            "flow" => node!([1, 2]),
+        })
+    );
+}
+
+#[test]
+fn test_error_position() {
+    let e = parse::<repr::RcRepr>("a: 1\n中文: b\n中文2").unwrap_err();
+    assert_eq!(e.to_string(), "invalid map splitter: \n\n3:4\n中文2\n   ^");
+}
+
+#[test]
+fn test_number_separator() {
+    let mut root =
+        parse("[1_000, 1.234_56, 0xF_F, 1__0, _1, 1_, 1_.5, 1._5]").unwrap_or_else(show_err);
+    let node = root.remove(0);
+    assert_eq!(
+        node,
+        node!([1000, 1.23456, 255, "1__0", "_1", "1_", "1_.5", "1._5"])
+    );
+    assert_eq!(node[Ind(0)].as_int().unwrap(), 1000);
+    assert_eq!(node[Ind(1)].as_float().unwrap(), 1.23456);
+    assert_eq!(parse("10.0").unwrap_or_else(show_err), vec![node!(10.)]);
+}
+
+#[test]
+fn test_round_trip() {
+    for doc in [
+        include_str!("complete_doc.yaml"),
+        include_str!("seq.yaml"),
+        include_str!("map.yaml"),
+        include_str!("literal.yaml"),
+        "[true, '123', 'a: b', '- x', ' lead', 'null', '~', 'x #y', '1.5', '[a]', '#c', '']",
+        "{a: {}, b: [], c: [[], {}]}",
+        r#"["\e\N\t \"q\" \\", "\0"]"#,
+    ] {
+        let nodes = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+        let s = dump(&nodes, &[]);
+        assert_eq!(parse(&s).unwrap_or_else(show_err), nodes, "{s}");
+    }
+}
+
+#[test]
+fn test_dump_flow() {
+    let anchors = parser::Anchors::new();
+    for (doc, ans) in [
+        ("{a: 1, b: [2, 3]}", "{a: 1, b: [2, 3]}"),
+        ("a: {}\nb: []", "{a: {}, b: []}"),
+        (
+            "- 'x, y'\n- '[z]'\n- 'true'\n- {c: d}",
+            "['x, y', '[z]', 'true', {c: d}]",
+        ),
+        ("? [a, b]\n: 1.0", "{[a, b]: 1.0}"),
+    ] {
+        let nodes = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+        let s = dumper::Dumper::new(&nodes[0], &anchors).flow(true).dump();
+        assert_eq!(s, ans);
+        assert_eq!(parse(&s).unwrap_or_else(show_err), nodes);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize() {
+    let doc = "a: [1, 0x10, 2e3, 18446744073709551616]\nb: ~\nc: {d: true, e: f}";
+    let node = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let ans = node!({
+        "a" => node!([1, 16, 2000., 18446744073709551616u128]),
+        "b" => (),
+        "c" => node!({"d" => true, "e" => "f"}),
+    });
+    assert_eq!(serde::to_node(&node).unwrap(), ans);
+    let ans =
+        "a:\n  - 1\n  - 16\n  - 2000.0\n  - 18446744073709551616\nb: null\nc:\n  d: true\n  e: f\n";
+    assert_eq!(serde::to_string(&node).unwrap().replace("\r\n", "\n"), ans);
+    let (nodes, _) = parse_cyclic::<repr::RcRepr>("- &a 1\n- *a").unwrap_or_else(show_err);
+    assert!(serde::to_node(&nodes[0]).is_err());
+    // The large radix integers
+    let doc = "[0x10000000000000000, 0o7777777777777777777777777, 0x1_0000_0000_0000_0000_0000_0000_0000_0000]";
+    let node = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(
+        serde::to_node(&node).unwrap(),
+        node!([
+            18446744073709551616u128,
+            37778931862957161709567u128,
+            340282366920938463463374607431768211456.
+        ])
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_deserialize_number() {
+    let root = parse::<repr::RcRepr>("[300, -1, 0x10, 2]").unwrap_or_else(show_err);
+    let node = &root[0];
+    assert!(serde::from_node::<Vec<u8>, _>(node).is_err());
+    assert!(serde::from_node::<Vec<u32>, _>(node).is_err());
+    assert_eq!(
+        serde::from_node::<Vec<i16>, _>(node).unwrap(),
+        [300, -1, 16, 2]
+    );
+    assert_eq!(
+        serde::from_node::<Vec<f64>, _>(node).unwrap(),
+        [300., -1., 16., 2.]
+    );
+    let e = serde::from_node::<Vec<u8>, _>(node).unwrap_err();
+    assert_eq!(e.msg, "invalid value: 300, expected an integer in range");
+    assert_eq!(e.pos, 1);
+    let root = parse::<repr::RcRepr>("[18446744073709551615, 0xffffffffffffffffffffffffffffffff]")
+        .unwrap_or_else(show_err);
+    let v = root[0].as_seq().unwrap();
+    assert_eq!(serde::from_node::<u64, _>(&v[0]).unwrap(), u64::MAX);
+    assert_eq!(
+        serde::from_node::<i128, _>(&v[0]).unwrap(),
+        u64::MAX as i128
+    );
+    assert!(serde::from_node::<i64, _>(&v[0]).is_err());
+    assert_eq!(serde::from_node::<u128, _>(&v[1]).unwrap(), u128::MAX);
+    assert!(serde::from_node::<i128, _>(&v[1]).is_err());
+    // Untyped integer is not converted to the lossy float
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(untagged)]
+    enum Number {
+        Int(u64),
+        Float(f64),
+    }
+    assert_eq!(
+        serde::from_node::<Number, _>(&v[0]).unwrap(),
+        Number::Int(u64::MAX)
+    );
+}
+
+#[test]
+fn test_entries() {
+    let node = parse::<repr::RcRepr>("{b: 1, a: 2, c: 3}")
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let entries = node
+        .entries()
+        .unwrap()
+        .map(|(k, v)| (k.as_str().unwrap(), v.as_int().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(entries, [("b", 1), ("a", 2), ("c", 3)]);
+    assert!(node["a"].entries().is_err());
+}
+
+#[test]
+fn test_get() {
+    let node = parse::<repr::RcRepr>("a: [x, y]\nb: 20")
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(node.get("b").unwrap(), &node!(20));
+    assert_eq!(node.get("a").unwrap().get_ind(Ind(1)).unwrap(), &node!("y"));
+    // Absent key or index
+    assert_eq!(node.get("c"), Err(0));
+    assert_eq!(node["a"].get_ind(Ind(2)), Err(3));
+    // Wrong node type
+    assert_eq!(node["a"].get("x"), Err(3));
+    assert_eq!(node.get_ind(Ind(0)), Err(0));
+    assert_eq!(node["b"].get("b"), Err(13));
+}
+
+#[test]
+fn test_mutate() {
+    let mut root = parse::<repr::RcRepr>("a: 1\nb: [x, y]\nc: 3").unwrap_or_else(show_err);
+    let node = &mut root[0];
+    *node.get_mut("a").unwrap() = node!("z");
+    node["b"][Ind(0)] = node!(10);
+    node.get_mut("b")
+        .unwrap()
+        .get_ind_mut(Ind(1))
+        .unwrap()
+        .set_yaml(false);
+    assert!(node.get_mut("d").is_err());
+    assert_eq!(
+        node,
+        &node!({"a" => "z", "b" => node!([10, false]), "c" => 3})
+    );
+    let keys = node.entries().unwrap().map(|(k, _)| k.as_str().unwrap());
+    assert_eq!(keys.collect::<Vec<_>>(), ["a", "b", "c"]);
+    assert_eq!(
+        dump(&root, &[]),
+        "a: z\nb:\n  - 10\n  - false\nc: 3\n".replace('\n', dumper::NL)
+    );
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(node!(true).to_string(), "true");
+    assert_eq!(node!(123).to_string(), "123");
+    assert_eq!(YamlRc::from("hello").to_string(), "hello");
+    assert_eq!(node!("123").to_string(), "\"123\"");
+    let ans = "- a\n-\n  - 1\n  - 2".replace('\n', dumper::NL);
+    assert_eq!(node!(["a", node!([1, 2])]).to_string(), ans);
+    let node = parse::<repr::RcRepr>("a:\n  b: [1, {c: d}]\n  e: f")
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let ans = "a:\n  b:\n    - 1\n    - c: d\n  e: f".replace('\n', dumper::NL);
+    assert_eq!(node.to_string(), ans);
+}
+
+#[test]
+fn test_timestamp() {
+    let doc = "
+- 2001-12-14
+- 2021-05-01T12:30:00Z
+- 2021-05-01 12:30:00.5 +08:00
+- 2001-12-14 abc
+- '2001-12-14'";
+    let node = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let ans = [
+        "2001-12-14",
+        "2021-05-01T12:30:00Z",
+        "2021-05-01 12:30:00.5 +08:00",
+    ];
+    for (i, ans) in ans.into_iter().enumerate() {
+        assert_eq!(node[Ind(i)].as_timestamp().unwrap(), ans);
+        assert_eq!(
+            node[Ind(i)].tag(),
+            concat!(parser::tag_prefix!(), "timestamp")
+        );
+    }
+    assert_eq!(node[Ind(3)], node!("2001-12-14 abc"));
+    assert_eq!(node[Ind(4)], node!("2001-12-14"));
+    assert!(node[Ind(4)].as_timestamp().is_err());
+}
+
+#[test]
+fn test_version_directive() {
+    let mut loader = parser::Loader::<repr::RcRepr>::new(b"%YAML 1.2\n---\na: b");
+    let root = loader.parse().unwrap_or_else(show_err);
+    assert_eq!(root, [node!({"a" => "b"})]);
+    assert_eq!(loader.version(), Some((1, 2)));
+    let mut loader = parser::Loader::<repr::RcRepr>::new(b"a: b");
+    loader.parse().unwrap_or_else(show_err);
+    assert_eq!(loader.version(), None);
+    for (doc, name) in [
+        ("%YAML 1.x\n--- a", "version"),
+        ("%YAML 1\n--- a", "version"),
+        ("%YAML 2.0\n--- a", "unsupported version"),
+        ("%YAML 1.2\n%YAML 1.2\n--- a", "checked version"),
+    ] {
+        match parse::<repr::RcRepr>(doc) {
+            Err(parser::PError::Terminate { name: e, .. }) => assert_eq!(e, name),
+            r => panic!("{r:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_tag_directive() {
+    let doc = "\
+%TAG !e! tag:example.com,2000:app/
+---
+- !e!foo.bar 1
+- !!str 2
+- !local 3
+- !<tag:yaml.org,2002:int> 4
+";
+    let node = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(node[Ind(0)].tag(), "tag:example.com,2000:app/foo.bar");
+    assert_eq!(node[Ind(1)].tag(), concat!(parser::tag_prefix!(), "str"));
+    assert_eq!(node[Ind(2)].tag(), "local");
+    assert_eq!(node[Ind(3)].tag(), concat!(parser::tag_prefix!(), "int"));
+    match parse::<repr::RcRepr>("- !x!foo 1") {
+        Err(parser::PError::Terminate { name, .. }) => assert_eq!(name, "undefined tag handle"),
+        r => panic!("{r:?}"),
+    }
+}
+
+#[test]
+fn test_complex_key() {
+    let doc = "\
+? - a
+  - b
+: 1
+? [c, d]
+: - 2
+  - 3
+? {e: f}
+? g
+";
+    let node = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(node.get(node!(["a", "b"])).unwrap(), &node!(1));
+    assert_eq!(node.get(node!(["c", "d"])).unwrap(), &node!([2, 3]));
+    assert!(node.get(node!({"e" => "f"})).unwrap().is_null());
+    assert!(node.get("g").unwrap().is_null());
+    assert_eq!(node.as_map().unwrap().len(), 4);
+}
+
+#[test]
+fn test_tab_indentation() {
+    let e = parse::<repr::RcRepr>("a:\n\tb: 1").unwrap_err();
+    assert_eq!(e.to_string(), "invalid tab indentation: \n\n2:1\n\tb: 1\n^");
+    let e = parse::<repr::RcRepr>("a:\n  b:\n   \tc: 2").unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "invalid tab indentation: \n\n3:4\n   \tc: 2\n   ^"
+    );
+    let node = parse::<repr::RcRepr>("a: \"x\ty\"\nb: [c,\n\td]").unwrap_or_else(show_err);
+    assert_eq!(node, [node!({"a" => "x\ty", "b" => node!(["c", "d"])})]);
+}
+
+#[test]
+fn test_bom() {
+    let node = parse::<repr::RcRepr>("\u{FEFF}true").unwrap_or_else(show_err);
+    assert_eq!(node, [node!(true)]);
+    let node =
+        parse::<repr::RcRepr>("\u{FEFF}%YAML 1.2\n---\na: \u{FEFF}b").unwrap_or_else(show_err);
+    assert_eq!(node, [node!({"a" => "\u{FEFF}b"})]);
+}
+
+#[test]
+fn test_crlf() {
+    let doc =
+        "a: 1 # c\r\nb:\r\n  - x\r\n  - y\r\n---\r\n[c,\r\n d]\r\n---\r\ne: |\r\n  f\r\n  g\r\n";
+    let node = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+    assert_eq!(
+        node,
+        [
+            node!({"a" => 1, "b" => node!(["x", "y"])}),
+            node!(["c", "d"]),
+            node!({"e" => "f\ng\n"}),
+        ]
+    );
+    let node = parse::<repr::RcRepr>("a: 1\rb: \"c\\r\"").unwrap_or_else(show_err);
+    assert_eq!(node, [node!({"a" => 1, "b" => "c\r"})]);
+    let e = parse::<repr::RcRepr>("a: 1\r\nb: 1\r\nc").unwrap_err();
+    assert_eq!(e.to_string(), "invalid map splitter: \n\n3:2\nc\n ^");
+}
+
+#[test]
+fn test_escape() {
+    let doc = r#"
+a: "\u00e9\x41\U0001F600"
+b: "\0\a\b\t\n\v\f\r\e\ \"\/\\\N\_\L\P"
+c: "x\
+    y\t
+    z"
+"#;
+    let node = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+    assert_eq!(
+        node,
+        [node!({
+            "a" => "éA😀",
+            "b" => "\0\x07\x08\t\n\x0B\x0C\r\x1B \"/\\\u{85}\u{A0}\u{2028}\u{2029}",
+            "c" => "xy\t z",
+        })]
+    );
+    let e = parse::<repr::RcRepr>(r#"a: "\q""#).unwrap_err();
+    assert_eq!(e.to_string(), "invalid escape: \n\n1:5\na: \"\\q\"\n    ^");
+    for doc in [r#""\x4""#, r#""\uD800""#] {
+        match parse::<repr::RcRepr>(doc) {
+            Err(parser::PError::Terminate { name, .. }) => assert_eq!(name, "escape"),
+            r => panic!("{r:?}"),
+        }
+    }
+    assert_eq!(parser::Parser::escape(r"a\tb\\c"), "a\tb\\c");
+    let mut p = parser::Parser::new(br"\u00e9");
+    p.pos = 1;
+    assert_eq!(p.escape_seq().ok(), Some('é'));
+}
+
+#[test]
+fn test_single_quoted() {
+    let doc = r"
+a: 'it''s'
+b: 'c:\d\n'
+c: '  e\
+  f  '
+";
+    let node = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+    assert_eq!(
+        node,
+        [node!({"a" => "it's", "b" => "c:\\d\\n", "c" => "  e\\ f  "})]
+    );
+    for doc in ["a: 'b", "a: \"b"] {
+        match parse::<repr::RcRepr>(doc) {
+            Err(parser::PError::Terminate { name, .. }) => assert_eq!(name, "quoted string"),
+            r => panic!("{r:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_documents() {
+    let doc = "--- a: 1\n--- [b]\n--- {c";
+    let mut loader = parser::Loader::<repr::RcRepr>::new(doc.as_bytes());
+    let mut docs = loader.documents();
+    assert_eq!(
+        docs.next().unwrap().unwrap_or_else(show_err),
+        node!({"a" => 1})
+    );
+    assert_eq!(docs.next().unwrap().unwrap_or_else(show_err), node!(["b"]));
+    // The third document is not touched
+    assert!(loader.pos <= doc.rfind("---").unwrap());
+    let mut docs = loader.documents();
+    assert!(docs.next().unwrap().is_err());
+    assert!(docs.next().is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parse_reader() {
+    use std::io::{Cursor, ErrorKind};
+    let node = parse_reader::<repr::RcRepr, _>(Cursor::new(b"--- a\n--- [b]".to_vec())).unwrap();
+    assert_eq!(node, [node!("a"), node!(["b"])]);
+    for doc in [b"a: \xff".to_vec(), b"a: 'b".to_vec()] {
+        let e = parse_reader::<repr::RcRepr, _>(Cursor::new(doc)).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn test_parse_recover() {
+    let doc = "a: 1\nb: \"\\q\"\nc: [2]\nd: 'e\n";
+    let mut loader = parser::Loader::<repr::RcRepr>::new(doc.as_bytes());
+    let (root, errors) = loader.parse_recover();
+    assert_eq!(
+        root,
+        [node!({"a" => 1, "b" => (), "c" => node!([2]), "d" => ()})]
+    );
+    assert_eq!(
+        recover_errors(errors),
+        [("escape", 9), ("quoted string", 26)]
+    );
+    let doc = "a: [1,\n  2, \"\\q\",\n  3]\n- b\n---\n[\"\\z\"]\n";
+    let mut loader = parser::Loader::<repr::RcRepr>::new(doc.as_bytes());
+    let (root, errors) = loader.parse_recover();
+    assert_eq!(root, [node!({"a" => node!([1, 2, (), 3])}), node!([()])]);
+    assert_eq!(
+        recover_errors(errors),
+        [("escape", 13), ("map splitter", 26), ("escape", 33)]
+    );
+    let mut loader = parser::Loader::<repr::RcRepr>::new(b"--- a\n--- b: c");
+    let (root, errors) = loader.parse_recover();
+    assert_eq!(root, [node!("a"), node!({"b" => "c"})]);
+    assert!(errors.is_empty());
+}
+
+fn recover_errors(errors: Vec<parser::PError>) -> Vec<(&'static str, u64)> {
+    errors
+        .into_iter()
+        .map(|e| match e {
+            parser::PError::Terminate { name, pos, .. } => (name, pos),
+            e => panic!("{e}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_blank_lines() {
+    let doc = "a: \"b\"  \n   \nc: 1 # d\n  \n  # e\nf: [g]\n  ";
+    let node = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+    assert_eq!(node, [node!({"a" => "b", "c" => 1, "f" => node!(["g"])})]);
+}
+
+#[test]
+fn test_error_span() {
+    for (doc, span) in [
+        (r#"a: "\q""#, (4, 6)),
+        (r#"a: "\x4""#, (4, 7)),
+        (r#"a: "\U0011FFFF""#, (4, 14)),
+        ("a: *x1y", (3, 7)),
+        ("%YAML 1.x\n--- a", (6, 9)),
+        ("%TAG !e! tag:x/\n--- !f!y a", (20, 23)),
+    ] {
+        let e = parse::<repr::RcRepr>(doc).unwrap_err();
+        assert_eq!(e.span(), Some(span), "{e}");
+    }
+}
+
+#[test]
+fn test_error_kind() {
+    use parser::ErrorKind;
+    for (doc, kind) in [
+        ("a: 1\nb", ErrorKind::MapSplitter),
+        ("a:\n\tb: 1", ErrorKind::TabIndentation),
+        ("a: *b", ErrorKind::UndefinedAnchor),
+        ("[&a 1, &a 2]", ErrorKind::DuplicatedAnchor),
+        (r#"a: "\q""#, ErrorKind::Escape),
+        ("%YAML 2.0\n--- a", ErrorKind::UnsupportedVersion),
+    ] {
+        let e = parse::<repr::RcRepr>(doc).unwrap_err();
+        assert_eq!(e.kind(), Some(kind), "{e}");
+        assert!(e
+            .to_string()
+            .starts_with(&format!("invalid {}: ", kind.name())));
+    }
+    assert_eq!(parser::PError::Mismatch.kind(), None);
+    for &kind in ErrorKind::ALL {
+        assert_eq!(ErrorKind::from_name(kind.name()), Some(kind));
+    }
+    assert_eq!(ErrorKind::from_name("custom"), None);
+}
+
+#[test]
+fn test_is_methods() {
+    let n =
+        parse::<repr::RcRepr>("[~, true, 1, 1.5, a, 2001-12-14, [], {}]").unwrap_or_else(show_err);
+    let n = n[0].as_seq().unwrap();
+    let checks: [fn(&NodeRc) -> bool; 8] = [
+        NodeRc::is_null,
+        NodeRc::is_bool,
+        NodeRc::is_int,
+        NodeRc::is_float,
+        NodeRc::is_str,
+        NodeRc::is_timestamp,
+        NodeRc::is_seq,
+        NodeRc::is_map,
+    ];
+    for (i, f) in checks.iter().enumerate() {
+        for (j, n) in n.iter().enumerate() {
+            assert_eq!(f(n), i == j, "{i} {n:?}");
+        }
+    }
+    assert!(node!(*"a").is_alias());
+    assert!(!node!("a").is_alias());
+}
+
+#[test]
+fn test_len() {
+    let n = parse::<repr::RcRepr>("a: [1, 2, 3]\nb: {}\nc: héllo\nd: 12").unwrap_or_else(show_err);
+    let n = &n[0];
+    assert_eq!(n.len(), 4);
+    assert_eq!(n["a"].len(), 3);
+    assert_eq!(n["b"].len(), 0);
+    assert!(n["b"].is_empty());
+    assert_eq!(n["c"].len(), 5);
+    assert!(!n["c"].is_empty());
+    assert_eq!(n["d"].len(), 0);
+}
+
+#[test]
+fn test_contains() {
+    let n = parse::<repr::RcRepr>("a: [b, 1, [c]]\nd: ~").unwrap_or_else(show_err);
+    let n = &n[0];
+    assert!(n.contains_key("a"));
+    assert!(n.contains_key("d"));
+    assert!(!n.contains_key("b"));
+    assert!(n["a"].contains("b"));
+    assert!(n["a"].contains(1));
+    assert!(n["a"].contains(node!(["c"])));
+    assert!(!n["a"].contains("c"));
+    assert!(!n["a"].contains_key("b"));
+    assert!(!n.contains("a"));
+}
+
+#[test]
+fn test_into_owned() {
+    let n = parse::<repr::RcRepr>("a: [b, c]\nd: e")
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let shared = n.clone();
+    let mut m = n.into_map().unwrap();
+    // The shared data is not changed
+    m.remove(&node!("d"));
+    assert_eq!(shared.len(), 2);
+    let v = m.remove(&node!("a")).unwrap().into_seq().unwrap();
+    let s = v
+        .into_iter()
+        .map(|n| n.into_string().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(s, ["b", "c"]);
+    let e = shared["d"].clone();
+    let pos = e.pos();
+    assert_eq!(e.clone().into_seq(), Err(pos));
+    assert_eq!(e.clone().into_map().map(|_| ()), Err(pos));
+    assert_eq!(node!(1).into_string(), Err(0));
+    assert_eq!(e.into_string().unwrap(), "e");
+}
+
+#[test]
+fn test_build_node() {
+    let n = NodeRc::map([
+        ("name", NodeRc::from("Bob")),
+        ("tags", NodeRc::seq(["a", "b"])),
+        ("info", NodeRc::map([("age", 46), ("height", 180)])),
+    ]);
+    assert_eq!(
+        n,
+        node!({
+            "name" => "Bob",
+            "tags" => node!(["a", "b"]),
+            "info" => node!({"age" => 46, "height" => 180}),
+        })
+    );
+    assert_eq!(NodeRc::seq(Vec::<NodeRc>::new()), node!([]));
+    assert_eq!(NodeArc::map([(1, 2)]), node!(arc {1 => 2}));
+}
+
+#[test]
+fn test_number_accessors() {
+    let n =
+        parse::<repr::RcRepr>("[0x1_F, 0o7_7, 0b1_0, -12, 2.5, .nan, a]").unwrap_or_else(show_err);
+    let n = n[0].as_seq().unwrap();
+    let ints = n[..4]
+        .iter()
+        .map(|n| n.as_int().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(ints, [31, 63, 2, -12]);
+    assert_eq!(n[4].as_float().unwrap(), 2.5);
+    assert!(n[5].as_float().unwrap().is_nan());
+    assert_eq!(n[0].as_number().unwrap(), 31.);
+    assert_eq!(n[4].as_number().unwrap(), 2.5);
+    assert_eq!(n[4].as_int(), Err(n[4].pos()));
+    assert_eq!(n[0].as_float(), Err(n[0].pos()));
+    assert_eq!(n[6].as_number(), Err(n[6].pos()));
+}
+
+#[test]
+fn test_node_pos() {
+    let doc = "a: 1\n  # c\nbb:\n  - x\n  - {y: z}\n";
+    let n = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let line_col = |n: &NodeRc| pos_to_line_col(doc.as_bytes(), n.pos());
+    let (k, v) = n.entries().unwrap().nth(1).unwrap();
+    assert_eq!(line_col(k), (3, 1));
+    assert_eq!(line_col(v), (4, 3));
+    assert_eq!(line_col(&n["a"]), (1, 4));
+    assert_eq!(line_col(&v[Ind(1)]), (5, 5));
+    let (k, v) = v[Ind(1)].entries().unwrap().next().unwrap();
+    assert_eq!(line_col(k), (5, 6));
+    assert_eq!(line_col(v), (5, 9));
+}
+
+#[test]
+fn test_parser_pos() {
+    let doc = "- 中\n- b".as_bytes();
+    let p = parser::Parser::new(doc).try_pos(6).unwrap();
+    assert_eq!(p.food(), b"- b");
+    assert_eq!(parser::Parser::new(doc).try_pos(3).map(|p| p.pos), Err(3));
+    assert_eq!(parser::Parser::new(doc).try_pos(10).map(|p| p.pos), Err(10));
+    assert_eq!(parser::Parser::new(doc).try_pos(9).unwrap().food(), b"");
+    // Infallible form keeps the position
+    assert_eq!(parser::Parser::new(doc).pos(3).pos, 0);
+    assert_eq!(parser::Parser::new(doc).pos(6).pos, 6);
+}
+
+#[test]
+fn test_collection_anchor() {
+    let doc = "\
+flow: &flow {a: 1}
+block: &block !!map
+  b: 2
+seq: &seq
+  - c
+merged:
+  <<: *flow
+  d: *seq
+";
+    let mut loader = parser::Loader::<repr::RcRepr>::new(doc.as_bytes());
+    let root = loader.parse().unwrap_or_else(show_err);
+    let anchors = loader.get_anchors().remove(0);
+    assert_eq!(anchors["flow"], node!({"a" => 1}));
+    assert_eq!(anchors["block"], node!({"b" => 2}));
+    assert_eq!(
+        anchors["block"].tag(),
+        concat!(parser::tag_prefix!(), "map")
+    );
+    assert_eq!(anchors["seq"], node!(["c"]));
+    assert_eq!(root[0]["merged"], node!({"a" => 1, "d" => node!(["c"])}));
+}
+
+#[test]
+fn test_ordering() {
+    let doc = "[b, 10, ~, 0x2, [], {}, 1.5, a, 3, true, false, .nan, -.inf]";
+    let mut v = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err)[0]
+        .clone()
+        .into_seq()
+        .unwrap();
+    v.sort();
+    let ans = node!([
+        (),
+        false,
+        true,
+        2,
+        3,
+        10,
+        f64::NEG_INFINITY,
+        1.5,
+        f64::NAN,
+        "a",
+        "b",
+        node!([]),
+        node!({})
+    ]);
+    assert_eq!(node!(v), ans);
+}
+
+#[test]
+fn test_ordering_big_int() {
+    let doc = "[99999999999999999999, 1, -99999999999999999999999999999999999999999, \
+               0x1_0000_0000_0000_0000, 1_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000, \
+               -3, 340282366920938463463374607431768211456, 18446744073709551616]";
+    let n = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let mut v = n.as_seq().unwrap();
+    v.sort();
+    let ans = [
+        "-99999999999999999999999999999999999999999",
+        "-3",
+        "1",
+        "18446744073709551616",
+        "18446744073709551616",
+        "99999999999999999999",
+        "340282366920938463463374607431768211456",
+        "1_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000",
+    ];
+    let v = v.iter().map(|n| n.as_value().unwrap()).collect::<Vec<_>>();
+    assert_eq!(v[..3], ans[..3]);
+    assert_eq!(v[5..], ans[5..]);
+    assert!(v[3..5].contains(&"0x1_0000_0000_0000_0000"));
+    // The equality of the big numbers
+    let big = Yaml::Int("+0340282366920938463463374607431768211456".to_string());
+    assert_eq!(n[Ind(6)], NodeRc::new(big, 0, ""));
+    assert_eq!(n[Ind(3)], n[Ind(7)]);
+    assert_ne!(n[Ind(0)], n[Ind(2)]);
+    let set = n
+        .as_seq()
+        .unwrap()
+        .into_iter()
+        .collect::<alloc::collections::BTreeSet<_>>();
+    assert_eq!(set.len(), 7);
+    // The same value in different radixes
+    let n = parse::<repr::RcRepr>(
+        "[0x1_0000_0000_0000_0000_0000_0000_0000_0000, 340282366920938463463374607431768211456, \
+         0o4_000_000_000_000_000_000_000_000_000_000_000_000_000_000, 0xffffffffffffffffffffffffffffffffff, \
+         340282366920938463463374607431768211457]",
+    )
+    .unwrap_or_else(show_err)
+    .remove(0);
+    assert_eq!(n[Ind(0)], n[Ind(1)]);
+    assert_eq!(n[Ind(1)], n[Ind(2)]);
+    assert!(n[Ind(0)] < n[Ind(4)]);
+    assert!(n[Ind(4)] < n[Ind(3)]);
+    let set = n
+        .as_seq()
+        .unwrap()
+        .into_iter()
+        .collect::<alloc::collections::BTreeSet<_>>();
+    assert_eq!(set.len(), 3);
+    #[derive(Default)]
+    struct Bytes(Vec<u8>);
+    impl core::hash::Hasher for Bytes {
+        fn finish(&self) -> u64 {
+            0
+        }
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+    let hash = |n: &NodeRc| {
+        let mut h = Bytes::default();
+        core::hash::Hash::hash(n, &mut h);
+        h.0
+    };
+    assert_eq!(hash(&n[Ind(0)]), hash(&n[Ind(1)]));
+    assert_eq!(hash(&n[Ind(0)]), hash(&n[Ind(2)]));
+}
+
+#[test]
+fn test_transform() {
+    let doc = "\
+name: alice
+tags: [a, b]
+nested:
+  deep:
+    - x
+    - 1
+";
+    let n = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let n = n.transform(|n| match n.as_str() {
+        Ok(s) => NodeRc::new(s.to_uppercase(), n.pos(), n.tag()),
+        Err(_) => n,
+    });
+    let ans = node!({
+        "name" => "ALICE",
+        "tags" => node!(["A", "B"]),
+        "nested" => node!({"deep" => node!(["X", 1])}),
+    });
+    assert_eq!(n, ans);
+    assert_eq!(n["nested"]["deep"][Ind(0)].pos(), 47);
+    // Bottom-up order
+    let mut visited = Vec::new();
+    node!([1, node!([2]), 3]).transform(|n: NodeRc| {
+        visited.push(n.len());
+        n
+    });
+    assert_eq!(visited, [0, 0, 1, 0, 3]);
+}
+
+#[test]
+fn test_query() {
+    let doc = "\
+config:
+  servers:
+    - name: alpha
+      port: 80
+    - name: beta
+    - port: 8080
+  key with space: 1
+";
+    let n = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let names = n.query("$.config.servers[*].name");
+    assert_eq!(names, [&node!("alpha"), &node!("beta")]);
+    let ports = n.query("$.config.servers.*.port");
+    assert_eq!(ports, [&node!(80), &node!(8080)]);
+    assert_eq!(n.query("$.config.servers[1].name"), [&node!("beta")]);
+    assert_eq!(n.query("$.config['key with space']"), [&node!(1)]);
+    assert_eq!(n.query("$"), [&n]);
+    assert_eq!(n.query("$.config[*]").len(), 2);
+    // Missing segments and invalid paths
+    assert!(n.query("$.config.clients[*].name").is_empty());
+    assert!(n.query("$.config.servers[5]").is_empty());
+    assert!(n.query("$.config.servers[0]name").is_empty());
+    assert!(n.query("$.config.servers[0").is_empty());
+}
+
+#[test]
+fn test_multiline_plain() {
+    let doc = "\
+key: this is
+  a long value
+next: two
+
+  paragraphs
+seq:
+  - first
+    item
+  -
+    second item
+comment: value
+  # Terminated
+last: end
+";
+    let n = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let ans = node!({
+        "key" => "this is a long value",
+        "next" => "two\nparagraphs",
+        "seq" => node!(["first item", "second item"]),
+        "comment" => "value",
+        "last" => "end",
+    });
+    assert_eq!(n, ans);
+    assert_eq!(parse::<repr::RcRepr>("a\nb\n").unwrap()[0], node!("a b"));
+    // Terminated by a key or a comment line
+    let e = parse::<repr::RcRepr>("a: b\n  #c\n  d\n").unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::Indentation));
+    let e = parse::<repr::RcRepr>("- a\n  b: c\n").unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::MapKey));
+}
+
+#[test]
+fn test_strict_keys() {
+    let doc = "\
+a: 1
+b:
+  c: 2
+  d: {e: 3, e: 4}
+";
+    let load = |strict| {
+        parser::Loader::<repr::RcRepr>::new(doc.as_bytes())
+            .strict_keys(strict)
+            .parse()
+    };
+    let n = load(false).unwrap_or_else(show_err);
+    assert_eq!(n[0]["b"]["d"], node!({"e" => 4}));
+    let e = load(true).unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::DuplicatedKey));
+    assert_eq!(e.span(), Some((27, 28)));
+    assert!(e.to_string().ends_with("first defined at 4:7"), "{e}");
+    // Block map
+    let doc = "a: 1\nb: 2\n? a\n: 3\n";
+    let e = parser::Loader::<repr::RcRepr>::new(doc.as_bytes())
+        .strict_keys(true)
+        .parse()
+        .unwrap_err();
+    assert_eq!(e.span(), Some((12, 13)));
+    assert!(e.to_string().ends_with("first defined at 1:1"), "{e}");
+    // Nested maps are checked separately
+    let doc = "a: {a: 1}\nb: [{a: 1}, {a: 2}]\n";
+    let n = parser::Loader::<repr::RcRepr>::new(doc.as_bytes())
+        .strict_keys(true)
+        .parse();
+    assert!(n.is_ok());
+}
+
+#[test]
+fn test_set_omap() {
+    let doc = "\
+set: !!set
+  ? a
+  ? b
+flow set: !!set {c, d}
+omap: !!omap
+  - z: 1
+  - y: 2
+untagged:
+  - x: 1
+";
+    let n = parse::<repr::RcRepr>(doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let set = n["set"].as_set().unwrap().collect::<Vec<_>>();
+    assert_eq!(set, [&node!("a"), &node!("b")]);
+    let set = n["flow set"].as_set().unwrap().collect::<Vec<_>>();
+    assert_eq!(set, [&node!("c"), &node!("d")]);
+    let omap = n["omap"].as_omap().unwrap();
+    assert_eq!(omap, [(&node!("z"), &node!(1)), (&node!("y"), &node!(2))]);
+    // Tags are required
+    assert!(n["omap"].as_set().is_err());
+    assert!(n["set"].as_omap().is_err());
+    assert!(n["untagged"].as_omap().is_err());
+    // Each item of the ordered map must be a single pair
+    let n = parse::<repr::RcRepr>("!!omap\n- a: 1\n  b: 2\n").unwrap_or_else(show_err);
+    assert_eq!(n[0].as_omap(), Err(9));
+}
+
+#[test]
+fn test_dump_indent() {
+    let n = node!({
+        "a" => node!({"b" => node!([1, node!([2, 3])])}),
+        "c" => node!([node!({"d" => 4, "e" => node!({"f" => 5})})]),
+        "g" => "multi\nline\n",
+    });
+    let dump_with = |indent| {
+        dumper::Dumper::new(&n, &parser::Anchors::new())
+            .indent(indent)
+            .dump()
+            .replace(dumper::NL, "\n")
+    };
+    let doc2 = "
+a:
+  b:
+    - 1
+    -
+      - 2
+      - 3
+c:
+  - d: 4
+    e:
+      f: 5
+g: |
+  multi
+  line
+";
+    let doc4 = "
+a:
+    b:
+        - 1
+        -
+            - 2
+            - 3
+c:
+    -   d: 4
+        e:
+            f: 5
+g: |
+    multi
+    line
+";
+    assert_eq!(dump_with(2), doc2.trim());
+    assert_eq!(dump_with(4), doc4.trim());
+    assert_eq!(dump_with(0), dump_with(2));
+    for doc in [doc2, doc4] {
+        assert_eq!(parse::<repr::RcRepr>(doc).unwrap_or_else(show_err)[0], n);
+    }
+}
+
+#[test]
+fn test_style_round_trip() {
+    let doc = "\
+quoted: \"123\"
+int: 123
+single: 'it''s'
+double: \"a\\tb\"
+plain: text
+literal: |-
+  one line
+flow: ['x', \"y\", z]
+";
+    let root = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+    assert_eq!(root[0]["quoted"].style(), Style::DoubleQuoted);
+    assert_eq!(root[0]["int"].style(), Style::Plain);
+    assert_eq!(root[0]["literal"].style(), Style::Literal);
+    assert_eq!(root[0]["flow"].style(), Style::Plain);
+    assert_ne!(root[0]["quoted"], root[0]["int"]);
+    let ans = "\
+quoted: \"123\"
+int: 123
+single: 'it''s'
+double: \"a\\tb\"
+plain: text
+literal: |-
+  one line
+flow:
+  - 'x'
+  - \"y\"
+  - z
+";
+    assert_eq!(dump(&root, &[]).replace(dumper::NL, "\n"), ans);
+    let n = node!("123");
+    assert_eq!(
+        dump(&[n.with_style(Style::SingleQuoted)], &[]).trim(),
+        "'123'"
+    );
+}
+
+#[test]
+fn test_dump_alias() {
+    let doc = "\
+base: &base
+  name: x
+  tags:
+    - a
+    - b
+copy: *base
+items:
+  - *base
+  - &num 1
+  - *num
+";
+    let mut loader = parser::Loader::<repr::RcRepr>::new(doc.as_bytes());
+    let root = loader.parse().unwrap_or_else(show_err);
+    let anchors = loader.get_anchors();
+    let s = dump(&root, &anchors);
+    assert_eq!(s.matches("&base").count(), 1, "{s}");
+    assert_eq!(s.matches("*base").count(), 2, "{s}");
+    assert_eq!(s.matches("name: x").count(), 1, "{s}");
+    assert_eq!(s.replace(dumper::NL, "\n"), doc);
+    // The anchors are resolved back
+    assert_eq!(parse::<repr::RcRepr>(&s).unwrap_or_else(show_err), root);
+    // Equal data without sharing is not an alias
+    let n = node!([node!({"name" => "x"}), node!({"name" => "x"})]);
+    let s = dump(&[n], &anchors);
+    assert!(!s.contains(['&', '*']), "{s}");
+    // Single anchor reused
+    let doc = "a: &x shared\nb: *x\n";
+    let mut loader = parser::Loader::<repr::RcRepr>::new(doc.as_bytes());
+    let root = loader.parse().unwrap_or_else(show_err);
+    let s = dump(&root, &loader.get_anchors());
+    assert_eq!((s.matches('&').count(), s.matches('*').count()), (1, 1));
+}
+
+#[test]
+fn test_parse_single() {
+    let n = parse_single::<repr::RcRepr>("---\na: [1, 2]\n").unwrap_or_else(show_err);
+    assert_eq!(n, node!({"a" => node!([1, 2])}));
+    let n = parse_single::<repr::RcRepr>("%YAML 1.2\n--- a\n...\n# end\n").unwrap_or_else(show_err);
+    assert_eq!(n, node!("a"));
+    // Empty stream is a null document
+    for doc in ["", "\n", "# comment\n"] {
+        assert_eq!(
+            parse_single::<repr::RcRepr>(doc).unwrap_or_else(show_err),
+            node!(())
+        );
+    }
+    // Multiple documents
+    let e = parse_single::<repr::RcRepr>("a: 1\n---\nb: 2\n").unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::DocumentSplitter));
+    assert_eq!(e.span(), Some((5, 8)));
+    let e = parse_single::<repr::RcRepr>("a\n...\nb\n").unwrap_err();
+    assert_eq!(e.span(), Some((6, 7)));
+}
+
+#[test]
+fn test_empty_doc() {
+    for doc in [
+        "",
+        "   ",
+        "\n\n  \n",
+        "# only\n  # comments\n",
+        "---\n",
+        "---\n...\n",
+        "...\n",
+    ] {
+        let root = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+        assert_eq!(root, [node!(())], "{doc:?}");
+    }
+    let root = parse::<repr::RcRepr>("---\n---\n# c\n---\na\n").unwrap_or_else(show_err);
+    assert_eq!(root, [node!(()), node!(()), node!("a")]);
+    // Not a document marker
+    let root = parse::<repr::RcRepr>("---\n---a\n").unwrap_or_else(show_err);
+    assert_eq!(root, [node!("---a")]);
+}
+
+#[test]
+fn test_nested_flow() {
+    const DOC: &str = "{a: [1, {b: [2, {c: []}]}], c: [[3], {}], d: [1, 2,]}";
+    let root = parse::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    assert_eq!(
+        root,
+        [node!({
+            "a" => node!([1, node!({"b" => node!([2, node!({"c" => node!([])})])})]),
+            "c" => node!([node!([3]), node!({})]),
+            "d" => node!([1, 2]),
+        })]
+    );
+    use parser::ErrorKind;
+    for (doc, kind, span) in [
+        ("[,]", ErrorKind::FlowSequenceItem, (1, 1)),
+        ("[a,,b]", ErrorKind::FlowSequenceItem, (3, 3)),
+        ("{,}", ErrorKind::FlowMapKey, (1, 1)),
+        ("[1, 2", ErrorKind::FlowSequenceTerminator, (5, 5)),
+        ("[\"a\" b]", ErrorKind::FlowSequenceTerminator, (4, 4)),
+        ("{a: 1", ErrorKind::FlowMapTerminator, (5, 5)),
+        ("{a: [1, {b: 2]}", ErrorKind::FlowMapTerminator, (13, 13)),
+    ] {
+        let e = parse::<repr::RcRepr>(doc).unwrap_err();
+        assert_eq!(e.kind(), Some(kind), "{doc:?}");
+        assert_eq!(e.span(), Some(span), "{doc:?}");
+    }
+}
+
+#[test]
+fn test_quoted_key() {
+    const DOC: &str = "\"a:b\": 1\n\"c: d\": 2\n'e: f': 3\n";
+    let root = parse::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    assert_eq!(root, [node!({"a:b" => 1, "c: d" => 2, "e: f" => 3})]);
+    let root = parse::<repr::RcRepr>("- \": \": [\"g: h\"]\n").unwrap_or_else(show_err);
+    assert_eq!(root, [node!([node!({": " => node!(["g: h"])})])]);
+    assert_eq!(root[0][Ind(0)][": "][Ind(0)].as_str(), Ok("g: h"));
+}
+
+#[test]
+fn test_binary() {
+    const DOC: &str = "\
+a: !!binary aGVsbG8gd29ybGQ=
+b: !!binary |
+  R0lGODlhDAAMAIQAAP//9/X1
+  7unp5WZmZgAAAOfn515eXg==
+c: !!binary ''
+d: aGVsbG8=
+";
+    let root = parse::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    let n = &root[0];
+    assert_eq!(n["a"].as_bytes(), Ok(&b"hello world"[..]));
+    assert_eq!(n["a"].tag(), concat!(parser::tag_prefix!(), "binary"));
+    assert_eq!(
+        n["b"].as_bytes().unwrap(),
+        b"GIF89a\x0c\0\x0c\0\x84\0\0\xff\xff\xf7\xf5\xf5\xee\xe9\xe9\xe5fff\0\0\0\xe7\xe7\xe7^^^"
+    );
+    assert_eq!(n["c"].as_bytes(), Ok(&b""[..]));
+    assert_eq!(n["d"].as_str(), Ok("aGVsbG8="));
+    assert_eq!(n["d"].as_bytes(), Err(n["d"].pos()));
+    // Round trip
+    for s in ["", "a", "ab", "abc", "abcd"] {
+        let doc = dump(&[node!({s => Node::from(Yaml::Bytes(s.into()))})], &[]);
+        let root = parse::<repr::RcRepr>(&doc).unwrap_or_else(show_err);
+        assert_eq!(root[0][s].as_bytes(), Ok(s.as_bytes()), "{doc}");
+    }
+    assert_eq!(
+        dump(
+            &[Node::<repr::RcRepr>::from(Yaml::Bytes(b"hello".to_vec()))],
+            &[]
+        ),
+        "!!binary aGVsbG8=\n"
+    );
+    for doc in ["!!binary aGVsbG8", "!!binary aGV=bG8=", "!!binary aGVsbG8*"] {
+        let e = parse::<repr::RcRepr>(doc).unwrap_err();
+        assert_eq!(e.kind(), Some(parser::ErrorKind::Binary), "{doc:?}");
+        assert_eq!(e.span(), Some((9, doc.len() as u64)), "{doc:?}");
+    }
+}
+
+#[test]
+fn test_eq_primitive() {
+    let root = parse::<repr::RcRepr>("a: 123\nb: hello\nc: [~, \"123\", 1e3, -1]\n")
+        .unwrap_or_else(show_err);
+    let n = &root[0];
+    assert_eq!(n["a"], 123i64);
+    assert_eq!(n["a"], 123u8);
+    assert_ne!(n["a"], 123.);
+    assert_ne!(n["a"], "123");
+    assert_eq!(n["b"], "hello");
+    assert_eq!(n["b"], *"hello");
+    assert_eq!(n["b"], "hello".to_string());
+    assert_ne!(n["b"], "world");
+    let c = n["c"].as_seq().unwrap();
+    assert_eq!(c[0], ());
+    assert_ne!(c[0], "");
+    assert_eq!(c[1], "123");
+    assert_ne!(c[1], 123);
+    assert_eq!(c[2], 1000.);
+    assert_eq!(c[2], 1000f32);
+    assert_eq!(c[3], -1);
+    assert_ne!(c[3], u64::MAX);
+    assert_eq!(node!(u64::MAX), u64::MAX);
+    assert_eq!(node!(i128::MIN), i128::MIN);
+    assert_ne!(node!(i128::MIN), i64::MIN);
+    let n = parse::<repr::RcRepr>("[18446744073709551615, 0xffff_ffff_ffff_ffff]")
+        .unwrap_or_else(show_err);
+    assert_eq!(n[0][Ind(0)], u64::MAX);
+    assert_eq!(n[0][Ind(1)], u128::from(u64::MAX));
+    assert_ne!(node!("true"), true);
+    assert_eq!(node!(f64::NAN), f64::NAN);
+}
+
+#[test]
+fn test_from_primitive() {
+    assert_eq!(NodeRc::from(3i64), node!(3));
+    assert_eq!(NodeRc::from(3u8).as_int(), Ok(3));
+    assert_eq!(NodeRc::from(-1.5f32).as_float(), Ok(-1.5));
+    assert_eq!(NodeRc::from(true).as_bool(), Ok(true));
+    assert_eq!(NodeRc::from('c').as_str(), Ok("c"));
+    assert_eq!(NodeRc::from("s").as_str(), Ok("s"));
+    assert_eq!(NodeRc::from("s".to_string()).as_str(), Ok("s"));
+    assert_eq!(NodeRc::from(()), node!(()));
+    assert_eq!(NodeRc::from(None::<&str>), node!(()));
+    assert_eq!(NodeRc::from(Some("s")), node!("s"));
+    let n = NodeRc::from(vec![1.into(), "a".into(), vec![true.into()].into()]);
+    assert_eq!(n, node!([1, "a", node!([true])]));
+    let n = vec![("a".into(), 1.into()), ("b".into(), Some(2).into())]
+        .into_iter()
+        .collect::<NodeRc>();
+    assert_eq!(n, node!({"a" => 1, "b" => 2}));
+    let v: Vec<NodeRc> = vec![1.into(), 2.into()];
+    assert_eq!(v.into_iter().collect::<NodeRc>(), node!([1, 2]));
+}
+
+#[test]
+fn test_remainder() {
+    const DOC: &str = "\"a: b\" # comment\nc: d\n";
+    let mut p = parser::Parser::new(DOC.as_bytes());
+    assert_eq!(p.string_quoted(b'"', b"\\\"").unwrap(), "a: b");
+    assert_eq!(p.remainder(), &DOC[6..]);
+    p.forward();
+    assert!(p.int().is_err());
+    assert_eq!(p.remainder(), &DOC[6..]);
+    let p = parser::Parser::new(DOC.as_bytes()).pos(DOC.len());
+    assert_eq!(p.remainder(), "");
+    let p = parser::Parser::new(b"a: \xff");
+    assert_eq!(p.remainder(), "a: ");
+}
+
+#[test]
+fn test_tag_percent() {
+    const DOC: &str = "\
+%TAG !e! tag:example.com,2000:
+---
+- !<tag:example.com,2002:foo%20bar> 1
+- !e!a%2Fb 2
+- !caf%C3%A9 3
+- !<!%21> 4
+";
+    let root = parse::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    let n = root[0].as_seq().unwrap();
+    assert_eq!(n[0].tag(), "tag:example.com,2002:foo bar");
+    assert_eq!(n[1].tag(), "tag:example.com,2000:a/b");
+    assert_eq!(n[2].tag(), "café");
+    assert_eq!(n[3].tag(), "!!");
+    for (doc, span) in [
+        ("!<a%G0> 1", (3, 6)),
+        ("!<a%2> 1", (3, 5)),
+        ("!a%C3 1", (1, 5)),
+    ] {
+        let e = parse::<repr::RcRepr>(doc).unwrap_err();
+        assert_eq!(e.kind(), Some(parser::ErrorKind::Escape), "{doc:?}");
+        assert_eq!(e.span(), Some(span), "{doc:?}");
+    }
+    // Round trip
+    let doc = dump(&root, &[]);
+    assert!(doc.contains("!<tag:example.com,2002:foo%20bar> 1"), "{doc}");
+    let n2 = parse::<repr::RcRepr>(&doc).unwrap_or_else(show_err);
+    assert_eq!(n2, root);
+    for (a, b) in n2[0].as_seq().unwrap().iter().zip(n) {
+        assert_eq!(a.tag(), b.tag());
+    }
+}
+
+#[test]
+fn test_indent_level() {
+    let mut p = parser::Parser::new(b"a:\n   b: 1\n");
+    assert_eq!(p.indent(1), 2);
+    p.take_while(|c| *c != b'\n', parser::TakeOpt::More(1))
+        .unwrap();
+    p.nl().unwrap();
+    p.ind_define(1).unwrap();
+    assert_eq!(p.indent(1), 3);
+    assert_eq!(p.food(), b"b: 1\n");
+    // Wrong indentation
+    for (doc, span) in [
+        ("a:\n  b: 1\n c: 2\n", (10, 11)),
+        ("a:\n  b: 1\n   c: 2\n", (12, 13)),
+        ("a:\n - 1\n  b: 2\n", (8, 10)),
+    ] {
+        let e = parse::<repr::RcRepr>(doc).unwrap_err();
+        assert_eq!(e.kind(), Some(parser::ErrorKind::Indentation), "{doc:?}");
+        assert_eq!(e.span(), Some(span), "{doc:?}");
+    }
+}
+
+#[test]
+fn test_special_float() {
+    let doc = "[.inf, .Inf, .INF, +.inf, inf, +INF, -.inf, -.Inf, -.INF, -inf, -Inf, \
+               .nan, .NaN, .NAN, nan, NaN, infinity, .na, +nan, iNf]";
+    let root = parser::Loader::<repr::RcRepr>::new(doc.as_bytes())
+        .undotted_float(true)
+        .parse()
+        .unwrap_or_else(show_err);
+    let v = root[0].as_seq().unwrap();
+    for n in &v[..6] {
+        assert_eq!(n.as_float(), Ok(f64::INFINITY), "{n:?}");
+    }
+    for n in &v[6..11] {
+        assert_eq!(n.as_float(), Ok(f64::NEG_INFINITY), "{n:?}");
+    }
+    for n in &v[11..16] {
+        assert!(n.as_float().unwrap().is_nan(), "{n:?}");
+    }
+    for n in &v[16..] {
+        assert!(n.as_float().is_err(), "{n:?}");
+        assert!(n.is_str(), "{n:?}");
+    }
+    let doc = dump(&root, &[]);
+    assert_eq!(parse::<repr::RcRepr>(&doc).unwrap_or_else(show_err), root);
+    // Strings by default
+    let root = parse::<repr::RcRepr>("[inf, -Inf, NAN, .inf]").unwrap_or_else(show_err);
+    assert_eq!(root[0], node!(["inf", "-Inf", "NAN", f64::INFINITY]));
+    let doc = dump(&root, &[]);
+    assert_eq!(parse::<repr::RcRepr>(&doc).unwrap_or_else(show_err), root);
+    assert_eq!(
+        doc,
+        "- inf\n- -Inf\n- NAN\n- .inf\n".replace('\n', dumper::NL)
+    );
+}
+
+#[test]
+fn test_as_str() {
+    let root = parse::<repr::RcRepr>("[hello, ~, 123, '']").unwrap_or_else(show_err);
+    let v = root[0].as_seq().unwrap();
+    assert_eq!(v[0].as_str(), Ok("hello"));
+    assert_eq!(v[1].as_str(), Err(v[1].pos()));
+    assert_eq!(v[2].as_str(), Err(v[2].pos()));
+    assert_eq!(v[3].as_str(), Ok(""));
+}
+
+#[test]
+fn test_int_key() {
+    let root = parse::<repr::RcRepr>("{1: a, 0x10: b, 1_000: c, 2.50: d, -1: e, '3': f}")
+        .unwrap_or_else(show_err);
+    let n = &root[0];
+    assert_eq!(n.get(1).unwrap(), &node!("a"));
+    assert_eq!(n[1u8], "a");
+    assert_eq!(n.get(16).unwrap(), &node!("b"));
+    assert_eq!(n.get(1000).unwrap(), &node!("c"));
+    assert_eq!(n.get(2.5).unwrap(), &node!("d"));
+    assert_eq!(n.get(-1).unwrap(), &node!("e"));
+    assert!(n.get(3).is_err());
+    assert!(n.get("1").is_err());
+    assert_eq!(n.get("3").unwrap(), &node!("f"));
+    assert!(n.contains_key(0x10));
+    let n = node!({0. => "a", f64::NAN => "b"});
+    assert_eq!(n.get(-0.).unwrap(), &node!("a"));
+    assert_eq!(n.get(f64::NAN).unwrap(), &node!("b"));
+}
+
+#[test]
+fn test_bool_1_1() {
+    const DOC: &[u8] = b"\
+a: on
+b: OFF
+c: Yes
+d: n
+e: \"on\"
+f: online
+g: true
+";
+    let n = parser::Loader::<repr::RcRepr>::new(DOC)
+        .parse()
+        .unwrap_or_else(show_err);
+    assert_eq!(n[0]["a"], "on");
+    assert_eq!(n[0]["b"], "OFF");
+    assert_eq!(n[0]["c"], "Yes");
+    assert_eq!(n[0]["d"], "n");
+    let n = parser::Loader::<repr::RcRepr>::new(DOC)
+        .bool_1_1(true)
+        .parse()
+        .unwrap_or_else(show_err);
+    assert_eq!(n[0]["a"], true);
+    assert_eq!(n[0]["b"], false);
+    assert_eq!(n[0]["c"], true);
+    assert_eq!(n[0]["d"], false);
+    assert_eq!(n[0]["e"], "on");
+    assert_eq!(n[0]["f"], "online");
+    assert_eq!(n[0]["g"], true);
+    let (n, _) = parser::Loader::<repr::RcRepr>::new(DOC)
+        .bool_1_1(true)
+        .parse_recover();
+    assert_eq!(n[0]["a"], true);
+    // Only the lowercase, capitalized and uppercase spellings
+    let n = parser::Loader::<repr::RcRepr>::new(b"[Y, NO, On, yEs, oN, oFF, nO]")
+        .bool_1_1(true)
+        .parse()
+        .unwrap_or_else(show_err);
+    assert_eq!(n[0], node!([true, false, true, "yEs", "oN", "oFF", "nO"]));
+}
+
+#[test]
+fn test_keyword_case() {
+    let root = parse::<repr::RcRepr>(
+        "[~, null, Null, NULL, true, True, TRUE, false, False, FALSE, nULL, tRUE, FaLsE]",
+    )
+    .unwrap_or_else(show_err);
+    let v = root[0].as_seq().unwrap();
+    for n in &v[..4] {
+        assert_eq!(*n, (), "{n:?}");
+    }
+    for n in &v[4..7] {
+        assert_eq!(*n, true, "{n:?}");
+    }
+    for n in &v[7..10] {
+        assert_eq!(*n, false, "{n:?}");
+    }
+    for n in &v[10..] {
+        assert!(n.is_str(), "{n:?}");
+    }
+    // Tagged as string
+    let root = parse::<repr::RcRepr>("[!!str TRUE, !!str Null, !!str 0x10, !!str, !!str 'a']")
+        .unwrap_or_else(show_err);
+    assert_eq!(root[0], node!(["TRUE", "Null", "0x10", "", "a"]));
+}
+
+#[test]
+fn test_visitor() {
+    #[derive(Default)]
+    struct Counter {
+        docs: usize,
+        scalars: usize,
+        events: Vec<&'static str>,
+    }
+
+    impl parser::Visitor<repr::RcRepr> for Counter {
+        fn doc_start(&mut self) {
+            self.docs += 1;
+        }
+
+        fn scalar(&mut self, _node: &NodeRc) {
+            self.scalars += 1;
+        }
+
+        fn seq_start(&mut self, _node: &NodeRc) {
+            self.events.push("[");
+        }
+
+        fn seq_end(&mut self) {
+            self.events.push("]");
+        }
+
+        fn map_start(&mut self, _node: &NodeRc) {
+            self.events.push("{");
+        }
+
+        fn map_end(&mut self) {
+            self.events.push("}");
+        }
+
+        fn alias(&mut self, _name: &str, _node: &NodeRc) {
+            self.events.push("*");
+        }
+    }
+
+    const DOC: &[u8] = b"\
+a:
+  - 1
+  - b: [2, 3]
+    c: {}
+d: e
+--- f
+";
+    let mut v = Counter::default();
+    parser::Loader::<repr::RcRepr>::new(DOC)
+        .parse_events(&mut v)
+        .unwrap_or_else(show_err);
+    assert_eq!(v.docs, 2);
+    assert_eq!(v.scalars, 9);
+    assert_eq!(v.events, ["{", "[", "{", "[", "]", "{", "}", "}", "]", "}"]);
+    let mut v = Counter::default();
+    parser::Loader::<repr::RcRepr>::new(b"&a [*a]")
+        .cyclic_mode(true)
+        .parse_events(&mut v)
+        .unwrap_or_else(show_err);
+    assert_eq!(v.events, ["[", "*", "]"]);
+    let mut v = Counter::default();
+    let e = parser::Loader::<repr::RcRepr>::new(b"a\n--- [b")
+        .parse_events(&mut v)
+        .unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::FlowSequenceTerminator));
+    assert_eq!((v.docs, v.scalars), (1, 1));
+}
+
+#[test]
+fn test_span() {
+    const DOC: &str = "\
+a: hello  # comment
+b: \"x\\ty\"
+c: [1, {d: 2.5}]
+e: long
+  text
+f: &x 10
+g: *x
+";
+    let root = parse::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    let n = &root[0];
+    let text = |n: &NodeRc| {
+        let (pos, end) = n.span();
+        &DOC[pos as usize..end as usize]
+    };
+    assert_eq!(text(&n["a"]), "hello");
+    assert_eq!(text(&n["b"]), "\"x\\ty\"");
+    assert_eq!(text(&n["c"]), "[1, {d: 2.5}]");
+    assert_eq!(text(&n["c"][Ind(1)]["d"]), "2.5");
+    assert_eq!(text(&n["e"]), "long\n  text");
+    assert_eq!(text(&n["f"]), "10");
+    assert_eq!(text(&n["g"]), "*x");
+    assert_eq!(text(n), DOC.trim_end());
+    let (k, _) = n.as_map().unwrap().into_iter().next().unwrap();
+    assert_eq!(text(&k), "a");
+    for (i, c) in "hello".chars().enumerate() {
+        let n = NodeRc::new(c, i as u64, "");
+        assert_eq!(n.span(), (i as u64, i as u64));
+    }
+}
+
+#[test]
+fn test_comment_eof() {
+    for doc in ["a: b # c", "- b # c", "'b' # c"] {
+        let n = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+        assert_eq!(n.len(), 1, "{doc}");
+    }
+}
+
+#[test]
+fn test_shared_alias() {
+    const DOC: &str = "\
+- &a
+  b: [1, 2, 3]
+- *a
+- c: *a
+";
+    let (root, anchors) = parse_cyclic::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    let n = root[0].resolve_anchors(&anchors[0]).unwrap();
+    assert!(n[Ind(0)].is_shared(&n[Ind(1)]));
+    assert!(n[Ind(1)].is_shared(&n[Ind(2)]["c"]));
+    assert!(n[Ind(0)].is_shared(&root[0][Ind(0)]));
+    assert!(!n.is_shared(&root[0]));
+    let n = parse::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    assert!(n[0][Ind(0)].is_shared(&n[0][Ind(1)]));
+    assert!(n[0][Ind(1)].is_shared(&n[0][Ind(2)]["c"]));
+}
+
+#[test]
+fn test_max_depth() {
+    let doc = "[".repeat(10000) + &"]".repeat(10000);
+    let e = parse::<repr::RcRepr>(&doc).unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::MaxDepth));
+    let doc = "- ".repeat(10000) + "a";
+    let e = parse::<repr::RcRepr>(&doc).unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::MaxDepth));
+    // The default depth
+    for doc in [
+        "[".repeat(128) + "a" + &"]".repeat(128),
+        "{a: ".repeat(128) + "b" + &"}".repeat(128),
+        "{? ".repeat(128) + "a" + &"}".repeat(128),
+        "[{a: ".repeat(64) + "b" + &"}]".repeat(64),
+        "- ".repeat(128) + "a",
+    ] {
+        parse::<repr::RcRepr>(&doc).unwrap_or_else(show_err);
+    }
+    let doc = "[".repeat(129) + "a" + &"]".repeat(129);
+    let e = parse::<repr::RcRepr>(&doc).unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::MaxDepth));
+}
+
+#[test]
+fn test_reset() {
+    let mut loader =
+        parser::Loader::<repr::RcRepr>::new(b"%TAG !e! tag:a.org,2000:\n---\n- !e!x [1, 2]");
+    let n = loader.parse().unwrap_or_else(show_err);
+    assert_eq!(n[0][Ind(0)].tag(), "tag:a.org,2000:x");
+    loader.reset(b"---\na: 1\n---\nb: 2\n");
+    let n = loader.parse().unwrap_or_else(show_err);
+    assert_eq!(n, [node!({"a" => 1}), node!({"b" => 2})]);
+    assert_eq!(n[0].pos(), 4);
+    loader.reset(b"!e!x a");
+    let e = loader.parse().unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::UndefinedTagHandle));
+}
+
+#[test]
+fn test_deep_merge() {
+    const DEFAULTS: &str = "\
+server:
+  host: localhost
+  port: 80
+  tls: {enabled: false, ciphers: [a, b]}
+plugins: [log]
+name: app
+";
+    const OVERRIDES: &str = "\
+server:
+  port: 8080
+  tls: {enabled: true, ciphers: [c]}
+plugins: [auth]
+name: null
+debug: true
+";
+    let defaults = parse::<repr::RcRepr>(DEFAULTS)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let overrides = parse::<repr::RcRepr>(OVERRIDES)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let mut n = defaults.clone();
+    n.deep_merge(&overrides, SeqMerge::Replace);
+    let tls = node!({"enabled" => true, "ciphers" => node!(["c"])});
+    let server = node!({"host" => "localhost", "port" => 8080, "tls" => tls});
+    let plugins = node!(["auth"]);
+    let expected = node!({"server" => server, "plugins" => plugins, "name" => (), "debug" => true});
+    assert_eq!(n, expected);
+    let keys = n["server"]
+        .as_map()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(keys, [node!("host"), node!("port"), node!("tls")]);
+    let mut n = defaults.clone();
+    n.deep_merge(&overrides, SeqMerge::Concat);
+    assert_eq!(n["server"]["tls"]["ciphers"], node!(["a", "b", "c"]));
+    assert_eq!(n["plugins"], node!(["log", "auth"]));
+    // The shared data is not changed
+    assert_eq!(defaults["server"]["port"], node!(80));
+    let mut n = node!("a");
+    n.deep_merge(&overrides, SeqMerge::Concat);
+    assert_eq!(n, overrides);
+}
+
+#[test]
+fn test_debug_pretty() {
+    const DOC: &str = "\
+a: !!str 1
+b:
+  - !x y
+  - []
+c: {}
+";
+    let n = parse::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    const EXPECTED: &str = r#"NodeMap {
+    NodeStr("a"): NodeStr("1") @9 !!str,
+    NodeStr("b") @11: NodeSeq @16 [
+        NodeStr("y") @21 !<x>,
+        NodeSeq @27 [],
+    ],
+    NodeStr("c") @30: NodeMap @33 {},
+}"#;
+    assert_eq!(format!("{:#?}", n[0]), EXPECTED);
+    assert_eq!(format!("{:?}", node!([1])), "NodeSeq([NodeInt(\"1\")])");
+}
+
+#[test]
+fn test_empty_value() {
+    let n = parse::<repr::RcRepr>("a:\nb: 1").unwrap_or_else(show_err);
+    assert_eq!(n[0], node!({"a" => (), "b" => 1}));
+    let n = parse::<repr::RcRepr>("a: # comment\nb:\n").unwrap_or_else(show_err);
+    assert_eq!(n[0], node!({"a" => (), "b" => ()}));
+    let n = parse::<repr::RcRepr>("- a:\n  b: 1\n- c:\n  - 2").unwrap_or_else(show_err);
+    let c = node!({"c" => node!([2])});
+    assert_eq!(n[0], node!([node!({"a" => (), "b" => 1}), c]));
+    let n = parse::<repr::RcRepr>("a:\n  b:\n  c: 1").unwrap_or_else(show_err);
+    assert_eq!(n[0], node!({"a" => node!({"b" => (), "c" => 1})}));
+}
+
+#[test]
+fn test_directive_end_line() {
+    for (doc, expected) in [
+        ("--- 42", node!(42)),
+        ("--- 42\n", node!(42)),
+        ("--- [1, 2] # c\n", node!([1, 2])),
+        ("--- |\n  text\n", node!("text\n")),
+        ("--- !!str 42", node!("42")),
+        ("a: 1\nb: 2\n", node!({"a" => 1, "b" => 2})),
+        ("42", node!(42)),
+    ] {
+        let n = parse::<repr::RcRepr>(doc).unwrap_or_else(show_err);
+        assert_eq!(n, [expected], "{doc:?}");
+    }
+    let n = parse::<repr::RcRepr>("a\n--- b\n--- c").unwrap_or_else(show_err);
+    assert_eq!(n, [node!("a"), node!("b"), node!("c")]);
+}
+
+#[test]
+fn test_or() {
+    const DOC: &str = "\
+timeout: ~
+retry: 3
+name:
+";
+    let n = parse::<repr::RcRepr>(DOC)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let default = node!(30);
+    assert_eq!(n["timeout"].or(&default), &default);
+    assert_eq!(n["name"].or(&default), &default);
+    assert_eq!(n["retry"].or(&default), &node!(3));
+    assert_eq!(n.get_or("delay", &default), &default);
+    assert_eq!(n.get_or("retry", &default).as_int(), Ok(3));
+    assert_eq!(node!([1]).get_or("a", &default), &default);
+}
+
+#[test]
+fn test_escape_code_point() {
+    for doc in [
+        r#"a: "\uD800""#,
+        r#"a: "x\uDFFF""#,
+        r#"a: "\U00110000""#,
+        r#"a: "\UFFFFFFFF""#,
+    ] {
+        let e = parse::<repr::RcRepr>(doc).unwrap_err();
+        assert_eq!(e.kind(), Some(parser::ErrorKind::Escape), "{doc}");
+        let (pos, end) = e.span().unwrap();
+        assert!(doc[pos as usize..end as usize].starts_with('\\'), "{doc}");
+        assert_eq!(end as usize, doc.len() - 1, "{doc}");
+    }
+    let n = parse::<repr::RcRepr>(r#""\U0010FFFF\uD7FF\uE000""#).unwrap_or_else(show_err);
+    assert_eq!(n[0], node!("\u{10FFFF}\u{D7FF}\u{E000}"));
+}
+
+#[test]
+fn test_inline_comment() {
+    const DOC: &str = "\
+a: 1  # c
+b: x y # c
+c: z\t# c
+d:
+  - p # c
+  - q\t\t# c
+e: w#v
+";
+    let n = parse::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    let d = node!(["p", "q"]);
+    assert_eq!(
+        n[0],
+        node!({"a" => 1, "b" => "x y", "c" => "z", "d" => d, "e" => "w#v"})
+    );
+    let n = parse::<repr::RcRepr>("a: x\n\n  \ty  z\n").unwrap_or_else(show_err);
+    assert_eq!(n[0], node!({"a" => "x\ny  z"}));
+}
+
+#[test]
+fn test_implicit_tag() {
+    let n = parse::<repr::RcRepr>("[123, 1.5, true, ~, abc, '1', 2001-12-14, [], {}]")
+        .unwrap_or_else(show_err);
+    let seq = n[0].as_seq().unwrap();
+    let tags = seq.iter().map(|n| n.tag()).collect::<Vec<_>>();
+    let expected = [
+        "int",
+        "float",
+        "bool",
+        "null",
+        "str",
+        "str",
+        "timestamp",
+        "seq",
+        "map",
+    ];
+    assert_eq!(tags.len(), expected.len());
+    for (tag, ty) in tags.into_iter().zip(expected) {
+        assert_eq!(tag.strip_prefix("tag:yaml.org,2002:"), Some(ty));
+    }
+}
+
+#[test]
+fn test_value_key() {
+    const DOC: &str = "\
+color:
+  =: red
+  dark: crimson
+size: {=: 1, max: 3}
+name: =
+";
+    let n = parse::<repr::RcRepr>(DOC)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(n["color"].default_value(), Some(&node!("red")));
+    assert_eq!(n["color"]["dark"], node!("crimson"));
+    assert_eq!(n["size"].default_value(), Some(&node!(1)));
+    assert_eq!(n["name"], node!("="));
+    assert_eq!(n.default_value(), None);
+    assert_eq!(n["name"].default_value(), None);
+}
+
+#[test]
+fn test_strict_escapes() {
+    let doc = br#"{a: "\q", b: "\q\t\x41"}"#;
+    let e = parser::Loader::<repr::RcRepr>::new(doc)
+        .parse()
+        .unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::Escape));
+    assert_eq!(e.span(), Some((5, 7)));
+    let n = parser::Loader::<repr::RcRepr>::new(doc)
+        .strict_escapes(false)
+        .parse()
+        .unwrap_or_else(show_err);
+    assert_eq!(n[0], node!({"a" => "\\q", "b" => "\\q\tA"}));
+    // The invalid hex escapes are still errors
+    let e = parser::Loader::<repr::RcRepr>::new(br#""\xZZ""#)
+        .strict_escapes(false)
+        .parse()
+        .unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::Escape));
+}
+
+#[test]
+fn test_take() {
+    const DOC: &str = "\
+a:
+  b: [1, {c: big}]
+d: 2
+";
+    let root = parse::<repr::RcRepr>(DOC)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let mut n = root.clone();
+    let c = n.get_mut("a").unwrap().get_mut("b").unwrap();
+    let c = c.get_ind_mut(Ind(1)).unwrap().take("c").unwrap();
+    assert_eq!(c, node!("big"));
+    assert_eq!(c.pos(), 16);
+    assert!(n["a"]["b"][Ind(1)]["c"].is_null());
+    assert_eq!(n["a"]["b"][Ind(1)]["c"].pos(), 16);
+    let one = n
+        .get_mut("a")
+        .unwrap()
+        .get_mut("b")
+        .unwrap()
+        .take_ind(Ind(0));
+    assert_eq!(one, Ok(node!(1)));
+    assert_eq!(
+        n["a"],
+        node!({"b" => node!([node!(()), node!({"c" => ()})])})
+    );
+    assert_eq!(n.take("x"), Err(0));
+    assert_eq!(n.take_ind(Ind(0)), Err(0));
+    // The shared data is not changed
+    assert_eq!(root["a"]["b"][Ind(1)]["c"], node!("big"));
+}
+
+#[test]
+fn test_colon_in_plain() {
+    const DOC: &str = "\
+url: http://example.com
+port: http://example.com:8080/a?b=c:d
+time: at 12:30
+a:b: c
+tab:\td
+list: [http://x.y, e:f]
+";
+    let n = parse::<repr::RcRepr>(DOC).unwrap_or_else(show_err);
+    assert_eq!(
+        n[0],
+        node!({
+            "url" => "http://example.com",
+            "port" => "http://example.com:8080/a?b=c:d",
+            "time" => "at 12:30",
+            "a:b" => "c",
+            "tab" => "d",
+            "list" => node!(["http://x.y", "e:f"]),
+        })
+    );
+}
+
+#[test]
+fn test_unicode_break() {
+    let n = parse::<repr::RcRepr>(r#"["a\Lb", "c\Pd", "e\Nf", "\_"]"#).unwrap_or_else(show_err);
+    assert_eq!(
+        n[0],
+        node!(["a\u{2028}b", "c\u{2029}d", "e\u{85}f", "\u{A0}"])
+    );
+    // They are not line breaks in YAML 1.2
+    let n = parse::<repr::RcRepr>("a: b\u{2028}c\u{85}d\ne: 'f\u{2029}g'").unwrap_or_else(show_err);
+    assert_eq!(
+        n[0],
+        node!({"a" => "b\u{2028}c\u{85}d", "e" => "f\u{2029}g"})
+    );
+}
+
+#[test]
+fn test_peek() {
+    let doc = "a: 文字\n".as_bytes();
+    let mut p = parser::Parser::new(doc);
+    assert_eq!(p.peek(), Some('a'));
+    assert!(p.peek_seq(b"a: "));
+    assert!(!p.peek_seq(b"b"));
+    assert_eq!((p.pos, p.eaten), (0, 0));
+    p.sym_seq(b"a: ").unwrap();
+    assert_eq!(p.peek(), Some('文'));
+    assert_eq!(p.pos, 3);
+    p.pos = doc.len();
+    assert_eq!(p.peek(), None);
+    assert!(p.peek_seq(b""));
+    assert_eq!(parser::Parser::new(b"\xFFa").peek(), None);
+}
+
+#[test]
+fn test_dump_canonical_numbers() {
+    use dumper::Dumper;
+    const DOC: &str = "\
+hex: 0x1A
+oct: 0o17
+sep: 1_000
+float: 1_000.5
+exp: 1E3
+inf: -.INF
+";
+    let n = parse::<repr::RcRepr>(DOC)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let anchors = parser::Anchors::new();
+    let doc = Dumper::new(&n, &anchors).dump();
+    assert_eq!(
+        doc,
+        DOC.trim_end()
+            .replace("-.INF", "-.inf")
+            .replace('\n', dumper::NL)
+    );
+    let doc = Dumper::new(&n, &anchors).canonical_numbers(true).dump();
+    const CANONICAL: &str = "\
+hex: 26
+oct: 15
+sep: 1000
+float: 1000.5
+exp: 1000.0
+inf: -.inf";
+    assert_eq!(doc, CANONICAL.replace('\n', dumper::NL));
+    let n2 = parse::<repr::RcRepr>(&doc)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(n, n2);
+    // Out of range
+    let n = parse::<repr::RcRepr>("0x10000000000000000")
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let doc = Dumper::new(&n, &anchors).canonical_numbers(true).dump();
+    assert_eq!(doc, "0x10000000000000000");
+}
+
+#[test]
+fn test_interpolate() {
+    const DOC: &str = "\
+db:
+  url: postgres://${USER}@${HOST}:5432
+  pool: 10
+paths: [\"${HOME}/a\", '${HOST}', \"${MISSING}\", $HOME, \"${open\"]
+";
+    let vars = |name: &str| match name {
+        "USER" => Some("admin".to_string()),
+        "HOST" => Some("localhost".to_string()),
+        "HOME" => Some("/root".to_string()),
+        _ => None,
+    };
+    let root = parse::<repr::RcRepr>(DOC)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let mut n = root.clone();
+    n.interpolate(vars, false).unwrap();
+    let db = node!({"url" => "postgres://admin@localhost:5432", "pool" => 10});
+    let paths = node!(["/root/a", "localhost", "${MISSING}", "$HOME", "${open"]);
+    assert_eq!(n, node!({"db" => db, "paths" => paths}));
+    assert_eq!(root["db"]["url"], node!("postgres://${USER}@${HOST}:5432"));
+    let mut n = root.clone();
+    assert_eq!(n.interpolate(vars, true), Err(86));
+    let mut names = Vec::new();
+    root.clone()
+        .interpolate(
+            |name| {
+                names.push(name.to_string());
+                None
+            },
+            false,
+        )
+        .unwrap();
+    assert_eq!(names, ["USER", "HOST", "HOME", "HOST", "MISSING"]);
+}
+
+#[test]
+fn test_from_bytes() {
+    let buf: Vec<u8> = "名前: ヤムル\nlist: [café, 🦀]\n".bytes().collect();
+    let mut loader = parser::Loader::<repr::RcRepr>::from_bytes(&buf).unwrap();
+    let n = loader.parse().unwrap_or_else(show_err).remove(0);
+    assert_eq!(
+        n,
+        node!({"名前" => "ヤムル", "list" => node!(["café", "🦀"])})
+    );
+    let mut bad = buf.clone();
+    bad[1] = 0xff;
+    match parser::Parser::from_bytes(&bad) {
+        Err(e) => assert_eq!(e.valid_up_to(), 0),
+        Ok(_) => panic!("invalid UTF-8 accepted"),
+    }
+    assert!(parser::Loader::<repr::RcRepr>::from_bytes(&bad).is_err());
+}
+
+#[test]
+fn test_doc_end_marker() {
+    let n = parse::<repr::RcRepr>("a: 1\n...\nb: 2\n...\n").unwrap_or_else(show_err);
+    assert_eq!(n, [node!({"a" => 1}), node!({"b" => 2})]);
+    let n = parse::<repr::RcRepr>("%YAML 1.2\n---\n- x\n...\n%YAML 1.2\n---\ny")
+        .unwrap_or_else(show_err);
+    assert_eq!(n, [node!(["x"]), node!("y")]);
+    let n = parse::<repr::RcRepr>("[a]\n...\nb").unwrap_or_else(show_err);
+    assert_eq!(n, [node!(["a"]), node!("b")]);
+    let e = parse::<repr::RcRepr>("[a]\nb").unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::DocumentSplitter));
+}
+
+#[test]
+fn test_render_error() {
+    const DOC: &str = "\
+list:
+\t- a
+key: {a: 1, b
+";
+    let e = parse::<repr::RcRepr>(DOC).unwrap_err();
+    assert_eq!(e.render(DOC), "invalid tab indentation: \n\n2:1\n\t- a\n^");
+    let doc = "a: 1\nkey:\t[b,\tc d: 2]";
+    let e = parse::<repr::RcRepr>(doc).unwrap_err();
+    assert_eq!(
+        e.render(doc),
+        "invalid map terminator: \n\n2:16\nkey:\t[b,\tc d: 2]\n    \t   \t      ^"
+    );
+    assert_eq!(PError::Mismatch.render(DOC), "not matched");
+}
+
+#[test]
+fn test_get_path() {
+    const DOC: &str = "\
+items:
+  - name: a
+    tags: [x, y]
+  - name: b
+";
+    let n = parse::<repr::RcRepr>(DOC)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(n.get_path(&["items", "0", "name"]), Ok(&node!("a")));
+    assert_eq!(n.get_path(&["items", "0", "tags", "1"]), Ok(&node!("y")));
+    assert_eq!(n.get_path(&["items", "1", "name"]), Ok(&node!("b")));
+    assert_eq!(n.get_path(&[]), Ok(&n));
+    assert_eq!(n.get_path(&["items", "2", "name"]), Err(9));
+    assert_eq!(n.get_path(&["items", "-1"]), Err(9));
+    assert_eq!(n.get_path(&["items", "0", "name", "x"]), Err(17));
+    // The integer keys, the string key takes precedence
+    let n = parse::<repr::RcRepr>("{1: one, 0x2: two, '3': three, 3: four}")
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(n.get_path(&["1"]), Ok(&node!("one")));
+    assert_eq!(n.get_path(&["2"]), Ok(&node!("two")));
+    assert_eq!(n.get_path(&["3"]), Ok(&node!("three")));
+    assert_eq!(n.get_path(&["4"]), Err(0));
+}
+
+#[test]
+fn test_quoted_folding() {
+    const DOC: &str = "\
+- \"a
+  b\"
+- 'a
+  b'
+- \"a \t
+  \tb
+
+  c\"
+- 'a
+
+
+  b'
+- \"a\\
+  b\"
+- \"
+  a  
+  \"
+";
+    let n = parse::<repr::RcRepr>(DOC)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(n, node!(["a b", "a b", "a b\nc", "a\n\nb", "ab", " a "]));
+    let n = parse::<repr::RcRepr>("k: \"a\r\n b\"").unwrap_or_else(show_err);
+    assert_eq!(n, [node!({"k" => "a b"})]);
+}
+
+#[test]
+fn test_line_column() {
+    let mut p = parser::Parser::new("名前: a\r\nb: 中文".as_bytes());
+    assert_eq!((p.line(), p.column()), (1, 1));
+    p.string_plain(0, false).unwrap();
+    assert_eq!((p.line(), p.column()), (1, 3));
+    p.sym(b':').unwrap();
+    assert_eq!((p.line(), p.column()), (1, 4));
+    p.pos += 2;
+    assert_eq!((p.line(), p.column()), (1, 6));
+    p.nl().unwrap();
+    assert_eq!((p.line(), p.column()), (2, 1));
+    p.pos = p.doc.len();
+    assert_eq!((p.line(), p.column()), (2, 6));
+}
+
+#[test]
+fn test_tagged_null() {
+    let n = parse::<repr::RcRepr>("- !!null ~\n- !!null\n- !x ~\n- ~\n- !!str ~")
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let seq = n.as_seq().unwrap();
+    let tags = seq
+        .iter()
+        .map(|n| (n.is_null(), n.tag()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tags,
+        [
+            (true, "tag:yaml.org,2002:null"),
+            (true, "tag:yaml.org,2002:null"),
+            (true, "x"),
+            (true, "tag:yaml.org,2002:null"),
+            (false, "tag:yaml.org,2002:str"),
+        ]
+    );
+    assert_eq!(seq[4], node!("~"));
+}
+
+#[test]
+fn test_json_stream() {
+    const DOC: &[u8] = br#"{"a": 1}{"b": [2, 3]}
+["c"] ["d"]
+---
+{"e": null}
+"#;
+    let load = || parser::Loader::<repr::RcRepr>::new(DOC);
+    let e = load().parse().unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::DocumentSplitter));
+    let n = load().json_stream(true).parse().unwrap_or_else(show_err);
+    let ans = [
+        node!({"a" => 1}),
+        node!({"b" => node!([2, 3])}),
+        node!(["c"]),
+        node!(["d"]),
+        node!({"e" => ()}),
+    ];
+    assert_eq!(n, ans);
+    // Only the flow collections can be split
+    for doc in [&b"[1]\nb"[..], b"- [1]\n[2]"] {
+        let e = parser::Loader::<repr::RcRepr>::new(doc)
+            .json_stream(true)
+            .parse()
+            .unwrap_err();
+        assert_eq!(e.kind(), Some(parser::ErrorKind::DocumentSplitter));
+    }
+}
+
+#[test]
+fn test_as_vec() {
+    const DOC: &str = "\
+hosts: [a, b]
+host: c
+none:
+map: {d: 1}
+";
+    let n = parse::<repr::RcRepr>(DOC)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let mut hosts = n["hosts"].as_vec();
+    hosts.extend(n["host"].as_vec());
+    hosts.extend(n["none"].as_vec());
+    assert_eq!(hosts, [node!("a"), node!("b"), node!("c")]);
+    assert_eq!(hosts[2].pos(), n["host"].pos());
+    assert_eq!(n["map"].as_vec(), [node!({"d" => 1})]);
+    // The items are shared with the original node
+    assert!(n["hosts"].as_vec()[0].is_shared(&n["hosts"][Ind(0)]));
+}
+
+#[test]
+fn test_misaligned_indent() {
+    const DOC: &str = "\
+server:
+  host: a
+   port: 80
+";
+    let e = parse::<repr::RcRepr>(DOC).unwrap_err();
+    assert_eq!(e.kind(), Some(parser::ErrorKind::Indentation));
+    assert_eq!(e.span(), Some((20, 21)));
+    assert!(e.to_string().ends_with("expected column 3, found 4"));
+    for (doc, msg) in [
+        ("a:\n  b: 1\n c: 2", "expected column 1, found 2"),
+        ("- a\n - b", "expected column 1, found 2"),
+        ("k:\n  - a\n   - b", "expected column 3, found 4"),
+    ] {
+        let e = parse::<repr::RcRepr>(doc).unwrap_err();
+        assert_eq!(e.kind(), Some(parser::ErrorKind::Indentation), "{doc}");
+        assert!(e.to_string().ends_with(msg), "{e}");
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_to_json_value() {
+    use serde_json::json;
+    const DOC: &str = "\
+name: app
+version: 1.5
+ports: [80, 0x1BB]
+max: 18446744073709551615
+limits:
+  cpu: .inf
+  memory: ~
+base: &base {debug: false}
+dev: *base
+1: one
+";
+    let n = parse::<repr::RcRepr>(DOC)
+        .unwrap_or_else(show_err)
+        .remove(0);
+    let ans = json!({
+        "name": "app",
+        "version": 1.5,
+        "ports": [80, 443],
+        "max": u64::MAX,
+        "limits": {"cpu": null, "memory": null},
+        "base": {"debug": false},
+        "dev": {"debug": false},
+        "1": "one",
+    });
+    assert_eq!(n.to_json_value(), Ok(ans));
+    // Unresolved alias and collection key
+    let n = parse_cyclic::<repr::RcRepr>("a: &a [*a]")
+        .unwrap_or_else(show_err)
+        .0
+        .remove(0);
+    assert_eq!(n.to_json_value(), Err(7));
+    let n = parse::<repr::RcRepr>("? [a]\n: b")
+        .unwrap_or_else(show_err)
+        .remove(0);
+    assert_eq!(n.to_json_value(), Err(2));
+    // The integers cannot be rounded
+    let n = parse::<repr::RcRepr>(
+        "[-9223372036854775809, 18446744073709551616, 0x1_0000_0000_0000_0000_0000_0000_0000_0000]",
+    )
+    .unwrap_or_else(show_err)
+    .remove(0);
+    for (i, pos) in [(0, 1), (1, 23), (2, 45)] {
+        assert_eq!(n[Ind(i)].to_json_value(), Err(pos));
+    }
+}
+
+#[test]
+fn test_dump_plain() {
+    let anchors = parser::Anchors::new();
+    let dump = |n: &NodeRc, flow| dumper::Dumper::new(n, &anchors).flow(flow).dump();
+    let plain = [
+        "a b",
+        "a#b",
+        "a:b",
+        "-a",
+        "http://x.y",
+        "1a",
+        "2001-12",
+        "inf",
+        "nan",
+    ];
+    let quoted = [
+        "",
+        " a",
+        "a:",
+        "a: b",
+        "a #b",
+        "- a",
+        "-",
+        "? a",
+        "-[",
+        "[a",
+        "&a",
+        "*a",
+        "!a",
+        "|",
+        "'a",
+        "@a",
+        "--- a",
+        "...",
+        "~",
+        "null",
+        "true",
+        "1",
+        "-1",
+        "0x1F",
+        "1.5",
+        "1e3",
+        ".inf",
+        "2001-12-14",
+    ];
+    for s in plain {
+        assert_eq!(dump(&node!(s), false), s);
+        assert_eq!(dump(&node!([s]), true), format!("[{s}]"));
+    }
+    for s in quoted {
+        let n = node!(s);
+        let doc = dump(&n, false);
+        assert_ne!(doc, s);
+        assert_eq!(parse::<repr::RcRepr>(&doc).unwrap_or_else(show_err), [n]);
+    }
+    for s in quoted.into_iter().chain(["a, b", "a[0]", "{a}"]) {
+        let n = node!([s]);
+        let doc = dump(&n, true);
+        assert_ne!(doc, format!("[{s}]"));
+        assert_eq!(parse::<repr::RcRepr>(&doc).unwrap_or_else(show_err), [n]);
+    }
+}
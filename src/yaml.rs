@@ -4,6 +4,7 @@ use alloc::{
     vec::Vec,
 };
 use core::{
+    cmp::Ordering,
     fmt::{Debug, Formatter},
     hash::{Hash, Hasher},
     iter::FromIterator,
@@ -20,6 +21,24 @@ macro_rules! impl_from {
     };
 }
 
+macro_rules! impl_to_int {
+    ($($(#[$meta:meta])* fn $id:ident -> $ty:ty)+) => {
+        $($(#[$meta])*
+        pub(crate) fn $id(s: &str) -> Result<$ty, core::num::ParseIntError> {
+            let s = s.replace('_', "");
+            if let Some(s) = s.strip_prefix("0x") {
+                <$ty>::from_str_radix(s, 16)
+            } else if let Some(s) = s.strip_prefix("0o") {
+                <$ty>::from_str_radix(s, 8)
+            } else if let Some(s) = s.strip_prefix("0b") {
+                <$ty>::from_str_radix(s, 2)
+            } else {
+                s.parse()
+            }
+        })+
+    };
+}
+
 macro_rules! impl_iter {
     ($(impl $($item:ty),+ => $ty:ident)+) => {
         $($(impl<R: Repr> FromIterator<$item> for Yaml<R> {
@@ -39,18 +58,146 @@ pub type Seq<R> = Vec<Node<R>>;
 /// The map data structure of YAML.
 pub type Map<R> = LinkedHashMap<Node<R>, Node<R>>;
 
-pub(crate) fn to_i64(s: &str) -> Result<i64, core::num::ParseIntError> {
-    if s.contains("0x") {
-        i64::from_str_radix(&s.replace("0x", ""), 16)
-    } else if s.contains("0o") {
-        i64::from_str_radix(&s.replace("0o", ""), 8)
+impl_to_int! {
+    fn to_i64 -> i64
+    fn to_i128 -> i128
+    #[cfg(feature = "serde")]
+    fn to_u128 -> u128
+}
+
+pub(crate) fn to_f64(s: &str) -> Result<f64, core::num::ParseFloatError> {
+    s.replace('_', "").parse()
+}
+
+/// The value of the integer which is out of [`i128`] range, returns
+/// `(negative, limbs)`, where the limbs are the base 2^32 digits from the
+/// most significant one without the leading zeros.
+fn big_int(s: &str) -> (bool, Vec<u32>) {
+    let s = s.replace('_', "").to_ascii_lowercase();
+    let (neg, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.strip_prefix('+').unwrap_or(&s)),
+    };
+    let (radix, s) = [("0x", 16), ("0o", 8), ("0b", 2)]
+        .into_iter()
+        .find_map(|(prefix, radix)| Some((radix, s.strip_prefix(prefix)?)))
+        .unwrap_or((10, s));
+    // Little-endian during the conversion
+    let mut limbs = Vec::new();
+    for c in s.chars() {
+        let mut carry = u64::from(c.to_digit(radix).unwrap_or_default());
+        for limb in &mut limbs {
+            let n = u64::from(*limb) * u64::from(radix) + carry;
+            *limb = n as u32;
+            carry = n >> 32;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+    }
+    limbs.reverse();
+    (neg && !limbs.is_empty(), limbs)
+}
+
+/// Convert the integer which is out of [`i128`] range to the nearest float.
+#[cfg(feature = "serde")]
+pub(crate) fn big_int_f64(s: &str) -> f64 {
+    let (neg, limbs) = big_int(s);
+    let f = limbs
+        .iter()
+        .fold(0., |f, &limb| f * 4294967296. + f64::from(limb));
+    if neg {
+        -f
     } else {
-        s.parse()
+        f
     }
 }
 
-pub(crate) fn to_f64(s: &str) -> Result<f64, core::num::ParseFloatError> {
-    s.parse()
+/// Compare the integers by their values, the integers out of [`i128`] range
+/// are compared by their big number values in any radix, so it never fails.
+fn cmp_int(s1: &str, s2: &str) -> Ordering {
+    match (to_i128(s1), to_i128(s2)) {
+        (Ok(n1), Ok(n2)) => n1.cmp(&n2),
+        (Ok(_), Err(_)) => cmp_int(s2, s1).reverse(),
+        (Err(_), Ok(_)) => {
+            if big_int(s1).0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }
+        (Err(_), Err(_)) => {
+            let (neg1, d1) = big_int(s1);
+            let (neg2, d2) = big_int(s2);
+            neg2.cmp(&neg1).then_with(|| {
+                let ord = (d1.len(), d1).cmp(&(d2.len(), d2));
+                if neg1 {
+                    ord.reverse()
+                } else {
+                    ord
+                }
+            })
+        }
+    }
+}
+
+/// Compare the floats by their values, NaN is greater than the other numbers.
+/// The invalid floats are greater than the valid ones and compared as strings.
+fn cmp_float(s1: &str, s2: &str) -> Ordering {
+    match (to_f64(s1), to_f64(s2)) {
+        (Ok(f1), Ok(f2)) => f1
+            .partial_cmp(&f2)
+            .unwrap_or_else(|| f1.is_nan().cmp(&f2.is_nan())),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => s1.cmp(s2),
+    }
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Decode the base64 string, the white spaces are ignored.
+pub(crate) fn from_base64(s: &str) -> Option<Vec<u8>> {
+    let s = s
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    if s.len() % 4 != 0 {
+        return None;
+    }
+    let mut v = Vec::with_capacity(s.len() / 4 * 3);
+    for (i, chunk) in s.chunks(4).enumerate() {
+        let last = i == s.len() / 4 - 1;
+        let pad = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if pad > 2 || (pad > 0 && !last) {
+            return None;
+        }
+        let mut n = 0u32;
+        for &c in &chunk[..4 - pad] {
+            n = n << 6 | BASE64.iter().position(|&b| b == c)? as u32;
+        }
+        n <<= 6 * pad;
+        v.extend_from_slice(&n.to_be_bytes()[1..4 - pad]);
+    }
+    Some(v)
+}
+
+/// Encode the bytes to base64 string with padding.
+pub(crate) fn to_base64(b: &[u8]) -> String {
+    let mut s = String::with_capacity(b.len().div_ceil(3) * 4);
+    for chunk in b.chunks(3) {
+        let mut buf = [0; 4];
+        buf[1..=chunk.len()].copy_from_slice(chunk);
+        let n = u32::from_be_bytes(buf);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                s.push(BASE64[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                s.push('=');
+            }
+        }
+    }
+    s
 }
 
 /// YAML data types, but it is recommended to use [`Node`] for shorten code.
@@ -64,6 +211,15 @@ pub(crate) fn to_f64(s: &str) -> Result<f64, core::num::ParseFloatError> {
 /// assert_eq!(YamlRc::Float("0.001".to_string()), 1e-3.into());
 /// ```
 ///
+/// The [`None`] value is converted to null.
+///
+/// ```
+/// use yaml_peg::YamlRc;
+///
+/// assert_eq!(YamlRc::Null, None::<i32>.into());
+/// assert_eq!(YamlRc::from(20), Some(20).into());
+/// ```
+///
 /// Also, the iterators can turned to sequence and map.
 ///
 /// ```
@@ -80,6 +236,20 @@ pub(crate) fn to_f64(s: &str) -> Result<f64, core::num::ParseFloatError> {
 /// ```
 ///
 /// The digit NaN (not-a-number) will be equal in the comparison.
+///
+/// The data types are totally ordered by their kinds first:
+/// null < boolean < integer < float < string < timestamp < binary < sequence <
+/// map < alias,
+/// then the numbers are compared by their values, and NaN is the greatest float.
+///
+/// ```
+/// use yaml_peg::YamlRc;
+///
+/// assert!(YamlRc::Null < YamlRc::from(false));
+/// assert!(YamlRc::from(0x10) > YamlRc::from(9));
+/// assert!(YamlRc::from(100) < YamlRc::from(0.5));
+/// assert!(YamlRc::from(f64::INFINITY) < YamlRc::from(f64::NAN));
+/// ```
 pub enum Yaml<R: Repr> {
     /// Null
     Null,
@@ -91,6 +261,10 @@ pub enum Yaml<R: Repr> {
     Float(String),
     /// String
     Str(String),
+    /// Timestamp, the date with optional time and time zone
+    Timestamp(String),
+    /// Binary data, decoded from the base64 string with the `!!binary` tag
+    Bytes(Vec<u8>),
     /// Sequence
     Seq(Seq<R>),
     /// Map
@@ -107,6 +281,8 @@ impl<R: Repr> Debug for Yaml<R> {
             Self::Int(s) => f.debug_tuple("Int").field(s).finish(),
             Self::Float(s) => f.debug_tuple("Float").field(s).finish(),
             Self::Str(s) => f.debug_tuple("Str").field(s).finish(),
+            Self::Timestamp(s) => f.debug_tuple("Timestamp").field(s).finish(),
+            Self::Bytes(b) => f.debug_tuple("Bytes").field(b).finish(),
             Self::Seq(s) => f.debug_tuple("Seq").field(s).finish(),
             Self::Map(m) => f.debug_tuple("Map").field(m).finish(),
             Self::Alias(a) => f.debug_tuple("Alias").field(a).finish(),
@@ -122,6 +298,8 @@ impl<R: Repr> Clone for Yaml<R> {
             Self::Int(s) => Self::Int(s.clone()),
             Self::Float(s) => Self::Float(s.clone()),
             Self::Str(s) => Self::Str(s.clone()),
+            Self::Timestamp(s) => Self::Timestamp(s.clone()),
+            Self::Bytes(b) => Self::Bytes(b.clone()),
             Self::Seq(s) => Self::Seq(s.clone()),
             Self::Map(m) => Self::Map(m.clone()),
             Self::Alias(a) => Self::Alias(a.clone()),
@@ -137,13 +315,22 @@ impl<R: Repr> Hash for Yaml<R> {
                 state.write_u8(2);
                 b.hash(state)
             }
+            // Hash the numbers by their values, same as the comparison
             Self::Int(s) => {
                 state.write_u8(3);
-                s.hash(state)
+                match to_i128(s) {
+                    Ok(n) => n.hash(state),
+                    Err(_) => big_int(s).hash(state),
+                }
             }
             Self::Float(s) => {
                 state.write_u8(4);
-                s.hash(state)
+                match to_f64(s) {
+                    Ok(f) if f.is_nan() => f64::NAN.to_bits().hash(state),
+                    // Positive and negative zeros are equal
+                    Ok(f) => (f + 0.).to_bits().hash(state),
+                    Err(_) => s.hash(state),
+                }
             }
             Self::Str(s) => {
                 state.write_u8(5);
@@ -161,6 +348,14 @@ impl<R: Repr> Hash for Yaml<R> {
                 state.write_u8(8);
                 a.hash(state)
             }
+            Self::Timestamp(s) => {
+                state.write_u8(9);
+                s.hash(state)
+            }
+            Self::Bytes(b) => {
+                state.write_u8(10);
+                b.hash(state)
+            }
         }
     }
 }
@@ -170,17 +365,10 @@ impl<R: Repr> PartialEq for Yaml<R> {
         match (self, other) {
             (Self::Null, Self::Null) => true,
             (Self::Bool(b1), Self::Bool(b2)) => b1 == b2,
-            (Self::Int(s1), Self::Int(s2)) => to_i64(s1).unwrap() == to_i64(s2).unwrap(),
-            (Self::Float(s1), Self::Float(s2)) => {
-                let f1 = to_f64(s1).unwrap();
-                let f2 = to_f64(s2).unwrap();
-                if f1.is_nan() && f2.is_nan() {
-                    true
-                } else {
-                    f1 == f2
-                }
-            }
-            (Self::Str(s1), Self::Str(s2)) => s1 == s2,
+            (Self::Int(s1), Self::Int(s2)) => cmp_int(s1, s2).is_eq(),
+            (Self::Float(s1), Self::Float(s2)) => cmp_float(s1, s2).is_eq(),
+            (Self::Str(s1), Self::Str(s2)) | (Self::Timestamp(s1), Self::Timestamp(s2)) => s1 == s2,
+            (Self::Bytes(b1), Self::Bytes(b2)) => b1 == b2,
             (Self::Seq(s1), Self::Seq(s2)) => s1 == s2,
             (Self::Map(m1), Self::Map(m2)) => m1 == m2,
             (Self::Alias(a1), Self::Alias(a2)) => a1 == a2,
@@ -191,6 +379,47 @@ impl<R: Repr> PartialEq for Yaml<R> {
 
 impl<R: Repr> Eq for Yaml<R> {}
 
+impl<R: Repr> Yaml<R> {
+    fn kind_order(&self) -> u8 {
+        match self {
+            Self::Null => 0,
+            Self::Bool(_) => 1,
+            Self::Int(_) => 2,
+            Self::Float(_) => 3,
+            Self::Str(_) => 4,
+            Self::Timestamp(_) => 5,
+            Self::Bytes(_) => 6,
+            Self::Seq(_) => 7,
+            Self::Map(_) => 8,
+            Self::Alias(_) => 9,
+        }
+    }
+}
+
+impl<R: Repr> PartialOrd for Yaml<R> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<R: Repr> Ord for Yaml<R> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Bool(b1), Self::Bool(b2)) => b1.cmp(b2),
+            (Self::Int(s1), Self::Int(s2)) => cmp_int(s1, s2),
+            (Self::Float(s1), Self::Float(s2)) => cmp_float(s1, s2),
+            (Self::Str(s1), Self::Str(s2)) | (Self::Timestamp(s1), Self::Timestamp(s2)) => {
+                s1.cmp(s2)
+            }
+            (Self::Bytes(b1), Self::Bytes(b2)) => b1.cmp(b2),
+            (Self::Seq(s1), Self::Seq(s2)) => s1.cmp(s2),
+            (Self::Map(m1), Self::Map(m2)) => m1.cmp(m2),
+            (Self::Alias(a1), Self::Alias(a2)) => a1.cmp(a2),
+            _ => self.kind_order().cmp(&other.kind_order()),
+        }
+    }
+}
+
 impl<R: Repr> From<()> for Yaml<R> {
     fn from(_: ()) -> Self {
         Self::Null
@@ -209,6 +438,12 @@ impl_from! {
     impl f32, f64 => Float
 }
 
+impl<R: Repr, T: Into<Self>> From<Option<T>> for Yaml<R> {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Self::Null, Into::into)
+    }
+}
+
 impl<R: Repr> From<Seq<R>> for Yaml<R> {
     fn from(a: Seq<R>) -> Self {
         Self::Seq(a)